@@ -37,6 +37,9 @@ fn query(criterion: &mut Criterion) {
             }
         });
     });
+    group.bench_function("bulk-loading-batch", |bencher| {
+        bencher.iter(|| srtree.query_batch(&pts, K));
+    });
 }
 
 pub fn benchmark(criterion: &mut Criterion) {
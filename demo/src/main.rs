@@ -1,15 +1,4 @@
-use srtree::{Metric, SRTree};
-
-struct Manhattan;
-impl Metric<f64> for Manhattan {
-    fn distance(&self, point1: &[f64], point2: &[f64]) -> f64 {
-        point1.iter().zip(point2).map(|(a, b)| (a - b).abs()).sum()
-    }
-
-    fn distance_squared(&self, _: &[f64], _: &[f64]) -> f64 {
-        0.
-    }
-}
+use srtree::{Manhattan, Params, SRTree};
 
 fn main() {
     let points = vec![
@@ -27,7 +16,8 @@ fn main() {
     println!("{distances:?}");
 
     // Build a tree with Manhattan distance
-    let tree = SRTree::default(&points, Manhattan).expect("Failed to build SRTree");
+    let tree = SRTree::new(&points, Params::default_params(), Manhattan::default())
+        .expect("Failed to build SRTree");
     let (indices, distances) = tree.query(&[8., 8.], 3);
     println!("{indices:?}"); // [4, 3, 2] (sorted by distance)
     println!("{distances:?}"); // [8., 10., 12.]
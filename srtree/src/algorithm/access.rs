@@ -0,0 +1,108 @@
+use crate::{measure::distance::Metric, SRTree};
+use ordered_float::Float;
+
+/// Read-only access to the nodes and points of a tree, so that the same search code runs over
+/// an in-memory tree and a memory-mapped index file.
+pub(crate) trait TreeAccess<T>
+where
+    T: Float,
+{
+    type Metric: Metric<T>;
+
+    fn metric(&self) -> &Self::Metric;
+
+    /// Returns `usize::MAX` if the tree is empty.
+    fn root(&self) -> usize;
+
+    fn is_leaf(&self, node_index: usize) -> bool;
+
+    /// Returns the number of children of a node, or of points of a leaf.
+    fn num_entries(&self, node_index: usize) -> usize;
+
+    fn entry(&self, node_index: usize, position: usize) -> usize;
+
+    fn center(&self, node_index: usize) -> &[T];
+
+    fn sphere_radius(&self, node_index: usize) -> T;
+
+    fn rect_low(&self, node_index: usize) -> &[T];
+
+    fn rect_high(&self, node_index: usize) -> &[T];
+
+    fn coords(&self, point_index: usize) -> &[T];
+
+    /// Returns the distance from a point to the centroid of its leaf.
+    fn point_radius(&self, point_index: usize) -> T;
+
+    /// Returns the minimum distance from a point to a node, bounded by both its sphere and rect.
+    fn min_distance(&self, point: &[T], node_index: usize) -> T {
+        let metric = self.metric();
+        let sphere_distance =
+            metric.distance(point, self.center(node_index)) - self.sphere_radius(node_index);
+        let closest_point: Vec<T> = point
+            .iter()
+            .zip(self.rect_low(node_index))
+            .zip(self.rect_high(node_index))
+            .map(|((coord, low), high)| coord.max(*low).min(*high))
+            .collect();
+        let rect_distance = metric.distance(point, &closest_point);
+        sphere_distance.max(rect_distance).max(T::zero())
+    }
+}
+
+impl<T, M> TreeAccess<T> for SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    type Metric = M;
+
+    fn metric(&self) -> &M {
+        &self.metric
+    }
+
+    fn root(&self) -> usize {
+        self.root_index
+    }
+
+    fn is_leaf(&self, node_index: usize) -> bool {
+        self.nodes[node_index].is_leaf()
+    }
+
+    fn num_entries(&self, node_index: usize) -> usize {
+        self.nodes[node_index].immed_children()
+    }
+
+    fn entry(&self, node_index: usize, position: usize) -> usize {
+        let node = &self.nodes[node_index];
+        if node.is_leaf() {
+            node.points()[position]
+        } else {
+            node.children()[position]
+        }
+    }
+
+    fn center(&self, node_index: usize) -> &[T] {
+        &self.nodes[node_index].sphere.center.coords
+    }
+
+    fn sphere_radius(&self, node_index: usize) -> T {
+        self.nodes[node_index].sphere.radius
+    }
+
+    fn rect_low(&self, node_index: usize) -> &[T] {
+        &self.nodes[node_index].rect.low
+    }
+
+    fn rect_high(&self, node_index: usize) -> &[T] {
+        &self.nodes[node_index].rect.high
+    }
+
+    fn coords(&self, point_index: usize) -> &[T] {
+        self.coords_of(point_index)
+    }
+
+    fn point_radius(&self, point_index: usize) -> T {
+        self.points[point_index].radius
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{measure::distance::Metric, SRTree};
+use ordered_float::Float;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// `Sync` if the `rayon` feature is enabled, so that batch queries can share the metric across
+/// threads, and implemented by every type otherwise.
+#[cfg(feature = "rayon")]
+pub trait MaybeSync: Sync {}
+
+#[cfg(feature = "rayon")]
+impl<M: Sync> MaybeSync for M {}
+
+/// `Sync` if the `rayon` feature is enabled, so that batch queries can share the metric across
+/// threads, and implemented by every type otherwise.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSync {}
+
+#[cfg(not(feature = "rayon"))]
+impl<M> MaybeSync for M {}
+
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T> + MaybeSync,
+{
+    /// Runs [`SRTree::query`] for every query point, in parallel if the `rayon` feature is
+    /// enabled. Results are in the same order as the queries.
+    pub fn query_batch(&self, queries: &[Vec<T>], k: usize) -> Vec<(Vec<usize>, Vec<T>)> {
+        #[cfg(feature = "rayon")]
+        let queries = queries.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let queries = queries.iter();
+        queries.map(|query| self.query(query, k)).collect()
+    }
+
+    /// Runs [`SRTree::query_radius`] for every query point, in parallel if the `rayon` feature
+    /// is enabled. Results are in the same order as the queries.
+    pub fn query_radius_batch(&self, queries: &[Vec<T>], radius: T) -> Vec<Vec<usize>> {
+        #[cfg(feature = "rayon")]
+        let queries = queries.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let queries = queries.iter();
+        queries
+            .map(|query| self.query_radius(query, radius))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "rayon"))]
+    use crate::{Euclidean, Metric};
+    use crate::{Params, SRTree};
+
+    #[test]
+    pub fn test_query_batch() {
+        let points: Vec<Vec<f64>> = (0..100).map(|i| vec![f64::from(i), 0.0]).collect();
+        let tree = SRTree::euclidean_with_params(&points, Params::new(2, 5).unwrap())
+            .expect("Failed to build SRTree");
+        let queries: Vec<Vec<f64>> = (0..100).rev().map(|i| vec![f64::from(i), 1.0]).collect();
+
+        let results = tree.query_batch(&queries, 3);
+        assert_eq!(results.len(), queries.len());
+        for (query, result) in queries.iter().zip(results) {
+            assert_eq!(result, tree.query(query, 3));
+        }
+
+        let results = tree.query_radius_batch(&queries, 2.0);
+        assert_eq!(results.len(), queries.len());
+        for (query, result) in queries.iter().zip(results) {
+            assert_eq!(result, tree.query_radius(query, 2.0));
+        }
+    }
+
+    /// A metric that cannot be shared across threads.
+    #[cfg(not(feature = "rayon"))]
+    struct LocalEuclidean(std::rc::Rc<Euclidean>);
+
+    #[cfg(not(feature = "rayon"))]
+    impl Metric<f64> for LocalEuclidean {
+        fn distance(&self, point1: &[f64], point2: &[f64]) -> f64 {
+            self.0.distance(point1, point2)
+        }
+
+        fn distance_squared(&self, point1: &[f64], point2: &[f64]) -> f64 {
+            self.0.distance_squared(point1, point2)
+        }
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    #[test]
+    pub fn test_query_batch_without_sync() {
+        let points: Vec<Vec<f64>> = (0..10).map(|i| vec![f64::from(i), 0.0]).collect();
+        let metric = LocalEuclidean(std::rc::Rc::new(Euclidean::default()));
+        let tree =
+            SRTree::new(&points, Params::default_params(), metric).expect("Failed to build SRTree");
+        let results = tree.query_batch(&[vec![2.25, 0.0]], 1);
+        assert_eq!(results, vec![(vec![2], vec![0.25])]);
+        assert_eq!(
+            tree.query_radius_batch(&[vec![2.25, 0.0]], 0.5),
+            vec![vec![2]]
+        );
+    }
+}
@@ -1,5 +1,4 @@
 use crate::{measure::distance::Metric, node::Node, SRTree};
-use num_traits::cast;
 use ordered_float::Float;
 
 impl<T, M> SRTree<T, M>
@@ -11,26 +10,24 @@ where
         if point_indices.is_empty() {
             return usize::MAX;
         }
+        let height = self.bulk_load_height(point_indices.len());
+        self.load_subtree(point_indices, height)
+    }
 
-        if point_indices.len() <= self.params.max_number_of_elements {
+    fn load_subtree(&mut self, point_indices: Vec<usize>, height: usize) -> usize {
+        if height == 1 {
             let leaf = Node::new_leaf(point_indices);
             let leaf_index = self.add_node(leaf);
             self.reshape(leaf_index);
             return leaf_index;
         }
 
-        let groups = self.create_entries(point_indices);
+        let groups = self.create_entries(point_indices, height);
         let children: Vec<usize> = groups
             .into_iter()
-            .map(|group| self.bulk_load(group))
+            .map(|group| self.load_subtree(group, height - 1))
             .collect();
 
-        let height = 1 + children
-            .iter()
-            .map(|child_index| self.nodes[*child_index].height)
-            .max()
-            .unwrap_or(0);
-
         let root = Node::new_node(children.clone(), height);
         let root_index = self.add_node(root);
         self.reshape(root_index);
@@ -41,7 +38,53 @@ where
         root_index
     }
 
-    fn create_entries(&self, point_indices: Vec<usize>) -> Vec<Vec<usize>> {
+    /// Returns the height of the lowest tree that can hold the given number of points, so that
+    /// all leaves of a bulk-loaded tree are at the same depth.
+    pub(crate) fn bulk_load_height(&self, num_points: usize) -> usize {
+        let max_entries = self.params.max_number_of_elements.max(2);
+        let mut height = 1;
+        let mut capacity = max_entries;
+        while capacity < num_points {
+            capacity = capacity.saturating_mul(max_entries);
+            height += 1;
+        }
+        height
+    }
+
+    /// Rewrites the coordinate buffer so that the points of every leaf occupy adjacent rows,
+    /// leaf after leaf in depth-first order.
+    pub(crate) fn reorder_rows(&mut self) {
+        let mut order = Vec::with_capacity(self.points.len());
+        let mut stack = Vec::new();
+        if self.root_index != usize::MAX {
+            stack.push(self.root_index);
+        }
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if node.is_leaf() {
+                order.extend(node.points());
+            } else {
+                stack.extend(node.children().iter().rev());
+            }
+        }
+        // removed points keep their coordinates, after the stored ones
+        order.extend((0..self.points.len()).filter(|point_index| !self.contains(*point_index)));
+
+        let mut coords = Vec::with_capacity(self.coords.len());
+        for (row, point_index) in order.into_iter().enumerate() {
+            coords.extend_from_slice(self.coords_of(point_index));
+            self.points[point_index].row = row;
+        }
+        self.coords = coords;
+    }
+
+    /// Splits the points of a node at the given height into groups, one for each child, along
+    /// the dimension of the highest variance.
+    pub(crate) fn create_entries(
+        &self,
+        point_indices: Vec<usize>,
+        height: usize,
+    ) -> Vec<Vec<usize>> {
         let variances = self.calculate_points_variance(&point_indices);
         let split_dim = variances
             .iter()
@@ -49,26 +92,32 @@ where
             .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
             .map(|(i, _)| i)
             .unwrap();
-        self.partition_points(point_indices, split_dim)
-    }
-
-    fn partition_points(&self, mut point_indices: Vec<usize>, split_dim: usize) -> Vec<Vec<usize>> {
-        if point_indices.len() <= self.params.max_number_of_elements {
-            return vec![point_indices];
-        }
-        let partition_size = calculate_internal_node_size(
+        let num_groups = num_groups(
             point_indices.len(),
-            self.params.max_number_of_elements,
+            height,
             self.params.min_number_of_elements,
+            self.params.max_number_of_elements,
         );
-        let mut entries = Vec::new();
-        while !point_indices.is_empty() {
-            let left = point_indices.len().saturating_sub(partition_size);
+        self.partition_points(point_indices, split_dim, num_groups)
+    }
+
+    /// Splits the points into groups of sizes differing by at most one, ordered along
+    /// `split_dim`.
+    fn partition_points(
+        &self,
+        mut point_indices: Vec<usize>,
+        split_dim: usize,
+        num_groups: usize,
+    ) -> Vec<Vec<usize>> {
+        let group_size = point_indices.len() / num_groups;
+        let num_larger_groups = point_indices.len() % num_groups;
+        let mut entries = Vec::with_capacity(num_groups);
+        for group in (0..num_groups).rev() {
+            let size = group_size + usize::from(group < num_larger_groups);
+            let left = point_indices.len() - size;
             point_indices.select_nth_unstable_by(left, |a, b| {
-                let (a, b) = (&self.points[*a], &self.points[*b]);
-                a.coords[split_dim]
-                    .partial_cmp(&b.coords[split_dim])
-                    .unwrap()
+                let (a, b) = (self.coords_of(*a), self.coords_of(*b));
+                a[split_dim].partial_cmp(&b[split_dim]).unwrap()
             });
             let slice = point_indices.split_off(left);
             entries.push(slice);
@@ -77,22 +126,15 @@ where
     }
 }
 
-fn calculate_internal_node_size(n: usize, leaf_size: usize, internal_node_fanout: usize) -> usize {
-    if n <= leaf_size {
-        return n;
-    }
-
-    let n: f64 = cast(n).unwrap();
-    let leaf_size: f64 = cast(leaf_size).unwrap();
-    let internal_node_fanout: f64 = cast(internal_node_fanout).unwrap();
-    if n < 2. * leaf_size {
-        return cast(n / 2.).unwrap();
-    }
-
-    let num_leaves = n / (2. * leaf_size);
-    let num_leaves_per_node = num_leaves.log(internal_node_fanout).floor();
-    let internal_node_size = leaf_size * internal_node_fanout.powf(num_leaves_per_node);
-    cast(internal_node_size).unwrap()
+/// Returns the number of children of a node holding `n` points at the given height, such that
+/// every child subtree can hold its share of the points without its nodes overflowing or, when
+/// possible, underflowing.
+fn num_groups(n: usize, height: usize, min_entries: usize, max_entries: usize) -> usize {
+    let exponent = u32::try_from(height - 1).unwrap_or(u32::MAX);
+    let capacity = max_entries.max(2).saturating_pow(exponent);
+    let occupancy = min_entries.max(1).saturating_pow(exponent);
+    let fewest_groups = (n + capacity - 1) / capacity;
+    fewest_groups.max(min_entries.min(n / occupancy)).min(n)
 }
 
 #[cfg(test)]
@@ -102,12 +144,14 @@ mod tests {
     use super::*;
 
     #[test]
-    pub fn test_internal_node_size() {
-        let n = 5000;
-        let leaf_size = 21;
-        let internal_node_fanout = 9;
-        let internal_node_size = calculate_internal_node_size(n, leaf_size, internal_node_fanout);
-        assert_eq!(internal_node_size, 1701);
+    pub fn test_num_groups() {
+        // children of height 2 hold between 4 * 4 and 10 * 10 points
+        assert_eq!(num_groups(1000, 3, 4, 10), 10);
+        assert_eq!(num_groups(150, 3, 4, 10), 4);
+        assert_eq!(num_groups(40, 3, 4, 10), 2);
+        // leaves hold between 4 and 10 points
+        assert_eq!(num_groups(11, 2, 4, 10), 2);
+        assert_eq!(num_groups(16, 2, 4, 10), 4);
     }
 
     #[test]
@@ -135,4 +179,29 @@ mod tests {
         assert_eq!(tree.nodes[0].rect.low, vec![5., 5.]);
         assert_eq!(tree.nodes[0].rect.high, vec![9., 9.]);
     }
+
+    #[test]
+    pub fn test_reorder_rows() {
+        let points: Vec<Vec<f64>> = (0..10).map(|i| vec![f64::from(i), 0.]).collect();
+        let tree = SRTree::euclidean_with_params(&points, Params::new(2, 5).unwrap())
+            .expect("Failed to build SRTree");
+        let mut rows = Vec::new();
+        for leaf_index in tree.nodes[tree.root_index].children() {
+            let mut leaf_rows: Vec<usize> = tree
+                .points_of(*leaf_index)
+                .iter()
+                .map(|point_index| tree.points[*point_index].row)
+                .collect();
+            leaf_rows.sort_unstable();
+            assert_eq!(
+                leaf_rows.last().unwrap() - leaf_rows[0],
+                leaf_rows.len() - 1
+            );
+            rows.extend(leaf_rows);
+        }
+        assert_eq!(rows, (0..10).collect::<Vec<usize>>());
+        for (point_index, point) in points.iter().enumerate() {
+            assert_eq!(tree.coords_of(point_index), point.as_slice());
+        }
+    }
 }
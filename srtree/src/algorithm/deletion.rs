@@ -0,0 +1,204 @@
+use crate::{measure::distance::Metric, SRTree};
+use ordered_float::Float;
+
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    /// Removes the point with the given index from the tree.
+    ///
+    /// Returns `false` if there is no such point or if it was already removed.
+    /// Indices of the remaining points are not affected.
+    pub fn remove(&mut self, point_index: usize) -> bool {
+        if !self.contains(point_index) {
+            return false;
+        }
+        let leaf_index = self.points[point_index].parent_index;
+        self.nodes[leaf_index]
+            .points_mut()
+            .retain(|index| *index != point_index);
+        self.points[point_index].parent_index = usize::MAX;
+        self.points[point_index].radius = T::zero();
+        self.num_points -= 1;
+        self.condense_tree(leaf_index);
+        true
+    }
+
+    /// Dissolves underfull nodes on the path from the given node to the root, refreshes the
+    /// shapes of the remaining ancestors and reinserts the entries of the dissolved nodes.
+    ///
+    /// Empty nodes are dissolved even with a minimum of zero, since they have no shape.
+    fn condense_tree(&mut self, mut node_index: usize) {
+        let min_number_of_elements = self.params.min_number_of_elements.max(1);
+        let mut removed_nodes = Vec::new();
+        let mut orphan_points = Vec::new();
+        let mut orphan_nodes = Vec::new();
+        while node_index != self.root_index {
+            let parent_index = self.nodes[node_index].parent_index;
+            let node = &self.nodes[node_index];
+            if node.immed_children() < min_number_of_elements {
+                if node.is_leaf() {
+                    orphan_points.extend(node.points());
+                } else {
+                    orphan_nodes.extend(node.children());
+                }
+                self.nodes[parent_index]
+                    .children_mut()
+                    .retain(|index| *index != node_index);
+                removed_nodes.push(node_index);
+            } else {
+                self.update_height(node_index);
+                self.reshape(node_index);
+            }
+            node_index = parent_index;
+        }
+
+        // the root is kept unless it runs out of entries or has only one child left
+        loop {
+            let root = &self.nodes[self.root_index];
+            if root.immed_children() == 0 {
+                removed_nodes.push(self.root_index);
+                self.root_index = usize::MAX;
+                break;
+            } else if !root.is_leaf() && root.immed_children() == 1 {
+                let child_index = root.children()[0];
+                removed_nodes.push(self.root_index);
+                self.root_index = child_index;
+                self.nodes[child_index].parent_index = usize::MAX;
+            } else {
+                self.update_height(self.root_index);
+                self.reshape(self.root_index);
+                break;
+            }
+        }
+
+        for subtree_index in orphan_nodes {
+            self.insert_subtree(subtree_index);
+        }
+        for point_index in orphan_points {
+            self.insert_point(point_index);
+        }
+
+        removed_nodes.sort_unstable();
+        for node_index in removed_nodes.into_iter().rev() {
+            self.remove_node(node_index);
+        }
+    }
+
+    /// Removes a detached node from the arena by moving the last node into its slot.
+    fn remove_node(&mut self, node_index: usize) {
+        let last_index = self.nodes.len() - 1;
+        self.nodes.swap_remove(node_index);
+        if node_index == last_index {
+            return;
+        }
+
+        let parent_index = self.nodes[node_index].parent_index;
+        if parent_index == usize::MAX {
+            if self.root_index == last_index {
+                self.root_index = node_index;
+            }
+        } else {
+            for child_index in self.nodes[parent_index].children_mut() {
+                if *child_index == last_index {
+                    *child_index = node_index;
+                }
+            }
+        }
+
+        if self.nodes[node_index].is_leaf() {
+            for point_index in self.nodes[node_index].points().clone() {
+                self.points[point_index].parent_index = node_index;
+            }
+        } else {
+            for child_index in self.nodes[node_index].children().clone() {
+                self.nodes[child_index].parent_index = node_index;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Params, SRTree};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    pub fn test_remove() {
+        let points = vec![vec![0., 0.], vec![1., 1.], vec![2., 2.]];
+        let mut tree = SRTree::euclidean(&points).expect("Failed to build SRTree");
+        assert!(tree.remove(0));
+        assert!(!tree.remove(0));
+        assert!(!tree.remove(3));
+        assert_eq!(tree.num_points(), 2);
+
+        let (indices, _) = tree.query(&[0., 0.], 3);
+        assert_eq!(indices, vec![1, 2]);
+
+        assert!(tree.remove(1));
+        assert!(tree.remove(2));
+        assert_eq!(tree.num_points(), 0);
+        assert_eq!(tree.num_nodes(), 0);
+        assert!(tree.query(&[0., 0.], 3).0.is_empty());
+        assert!(tree.query_radius(&[0., 0.], 1.).is_empty());
+
+        assert_eq!(tree.insert(vec![5., 5.]), 3);
+        assert_eq!(tree.query(&[0., 0.], 3).0, vec![3]);
+    }
+
+    #[test]
+    pub fn test_remove_with_condense() {
+        let points: Vec<Vec<f64>> = (0..100).map(|i| vec![f64::from(i), 0.]).collect();
+        let mut tree = SRTree::euclidean_with_params(&points, Params::new(2, 5).unwrap())
+            .expect("Failed to build SRTree");
+        for i in (0..100).step_by(3) {
+            assert!(tree.remove(i));
+        }
+
+        for node_index in 0..tree.num_nodes() {
+            let node = &tree.nodes[node_index];
+            if node_index != tree.root_index {
+                assert!(node.immed_children() >= 1);
+                let parent = &tree.nodes[node.parent_index];
+                assert!(parent.children().contains(&node_index));
+            }
+        }
+        for (point_index, point) in tree.points.iter().enumerate() {
+            if tree.contains(point_index) {
+                assert!(tree.points_of(point.parent_index).contains(&point_index));
+            }
+        }
+
+        let (indices, _) = tree.query(&[31., 0.], 3);
+        assert_eq!(indices[0], 31);
+        let mut indices = tree.query_radius(&[30., 0.], 2.);
+        indices.sort_unstable();
+        assert_eq!(indices, vec![28, 29, 31, 32]);
+    }
+
+    #[test]
+    pub fn test_churn_with_zero_minimum() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let points: Vec<Vec<f64>> = (0..50).map(|_| vec![rng.gen(), rng.gen()]).collect();
+        let mut tree = SRTree::euclidean_with_params(&points, Params::new(0, 4).unwrap())
+            .expect("Failed to build SRTree");
+        let mut live: Vec<usize> = (0..50).collect();
+        for _ in 0..500 {
+            match rng.gen_range(0..3) {
+                0 => live.push(tree.insert(vec![rng.gen(), rng.gen()])),
+                1 if !live.is_empty() => {
+                    let index = live.swap_remove(rng.gen_range(0..live.len()));
+                    assert!(tree.remove(index));
+                }
+                _ if !live.is_empty() => {
+                    let index = live[rng.gen_range(0..live.len())];
+                    assert!(tree.update(index, vec![rng.gen(), rng.gen()]));
+                }
+                _ => {}
+            }
+            assert_eq!(tree.validate(), Ok(()));
+            assert_eq!(tree.num_points(), live.len());
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{error::Error, measure::distance::Metric, node::Node, SRTree};
+use num_traits::cast;
+use ordered_float::{Float, OrderedFloat};
+
+/// An entry taken out of an overflowing node for forced reinsertion.
+enum Entry {
+    Point(usize),
+    Node(usize),
+}
+
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    /// Inserts a point into the tree and returns its index.
+    ///
+    /// # Panics
+    /// * If the dimension of `coords` differs from the dimension of the tree, or if `coords` has
+    ///   a NaN or infinite coordinate.
+    pub fn insert(&mut self, coords: Vec<T>) -> usize {
+        match self.try_insert(coords) {
+            Ok(point_index) => point_index,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Runs [`SRTree::insert`] after checking `coords`, reporting the index the point would
+    /// have had as the row of an error.
+    ///
+    /// # Errors
+    /// * `Error::DimensionMismatch` if the dimension of `coords` differs from the dimension of
+    ///   the tree.
+    /// * `Error::NonFiniteCoordinate` if `coords` has a NaN or infinite coordinate.
+    pub fn try_insert(&mut self, coords: Vec<T>) -> Result<usize, Error> {
+        self.check_point(self.points.len(), &coords)?;
+        let point_index = self.push_point(coords);
+        self.insert_point(point_index);
+        self.num_points += 1;
+        Ok(point_index)
+    }
+
+    /// Places a point that is already stored in `self.points` into a leaf.
+    pub(crate) fn insert_point(&mut self, point_index: usize) {
+        self.insert_point_with(point_index, &mut Vec::new());
+    }
+
+    /// Places a detached subtree under an internal node that is higher than the subtree.
+    pub(crate) fn insert_subtree(&mut self, subtree_index: usize) {
+        self.insert_subtree_with(subtree_index, &mut Vec::new());
+    }
+
+    /// Refreshes the shapes on the path from the given node to the root, treating overflows.
+    pub(crate) fn adjust_tree(&mut self, node_index: usize) {
+        self.adjust_tree_with(node_index, &mut Vec::new());
+    }
+
+    /// `reinserted_heights` holds the heights at which a forced reinsertion already happened
+    /// during the current insertion; each height gets at most one.
+    fn insert_point_with(&mut self, point_index: usize, reinserted_heights: &mut Vec<usize>) {
+        if self.root_index == usize::MAX {
+            let leaf_index = self.add_node(Node::new_leaf(vec![point_index]));
+            self.reshape(leaf_index);
+            self.root_index = leaf_index;
+            return;
+        }
+
+        let leaf_index = self.choose_leaf(point_index);
+        self.nodes[leaf_index].points_mut().push(point_index);
+        self.adjust_tree_with(leaf_index, reinserted_heights);
+    }
+
+    fn insert_subtree_with(&mut self, subtree_index: usize, reinserted_heights: &mut Vec<usize>) {
+        self.nodes[subtree_index].parent_index = usize::MAX;
+        if self.root_index == usize::MAX {
+            self.root_index = subtree_index;
+            return;
+        }
+
+        let height = self.nodes[subtree_index].height;
+        if self.nodes[self.root_index].height <= height {
+            self.attach_sibling(self.root_index, subtree_index);
+            return;
+        }
+
+        let center = &self.nodes[subtree_index].sphere.center;
+        let mut node_index = self.root_index;
+        while let Some(child_index) = self.nodes[node_index]
+            .children()
+            .iter()
+            .filter(|child_index| self.nodes[**child_index].height > height)
+            .min_by_key(|child_index| {
+                let child = &self.nodes[**child_index];
+                OrderedFloat(self.distance(center, &child.sphere.center))
+            })
+        {
+            node_index = *child_index;
+        }
+        self.nodes[node_index].children_mut().push(subtree_index);
+        self.nodes[subtree_index].parent_index = node_index;
+        self.adjust_tree_with(node_index, reinserted_heights);
+    }
+
+    /// Descends from the root to the leaf whose centroid is the closest to the given point.
+    fn choose_leaf(&self, point_index: usize) -> usize {
+        let coords = self.coords_of(point_index);
+        let mut node_index = self.root_index;
+        while !self.nodes[node_index].is_leaf() {
+            node_index = self.nodes[node_index]
+                .children()
+                .iter()
+                .min_by_key(|child_index| {
+                    let child = &self.nodes[**child_index];
+                    OrderedFloat(self.metric.distance(coords, &child.sphere.center.coords))
+                })
+                .copied()
+                .unwrap();
+        }
+        node_index
+    }
+
+    /// An overflowing node first gives away its farthest entries for reinsertion, once per
+    /// height, and is split otherwise. The root is always split.
+    fn adjust_tree_with(&mut self, mut node_index: usize, reinserted_heights: &mut Vec<usize>) {
+        let mut reinserted_entries = Vec::new();
+        loop {
+            self.update_height(node_index);
+            self.reshape(node_index);
+            if self.nodes[node_index].immed_children() > self.params.max_number_of_elements {
+                let height = self.nodes[node_index].height;
+                let mut entries = Vec::new();
+                if node_index != self.root_index && !reinserted_heights.contains(&height) {
+                    reinserted_heights.push(height);
+                    entries = self.take_farthest_entries(node_index);
+                }
+                if entries.is_empty() {
+                    let sibling_index = self.split(node_index);
+                    self.attach_sibling(node_index, sibling_index);
+                }
+                reinserted_entries.extend(entries);
+            }
+
+            let parent_index = self.nodes[node_index].parent_index;
+            if parent_index == usize::MAX {
+                break;
+            }
+            node_index = parent_index;
+        }
+
+        // closest entries are reinserted first
+        for entry in reinserted_entries.into_iter().rev() {
+            match entry {
+                Entry::Point(point_index) => {
+                    self.insert_point_with(point_index, reinserted_heights);
+                }
+                Entry::Node(subtree_index) => {
+                    self.insert_subtree_with(subtree_index, reinserted_heights);
+                }
+            }
+        }
+    }
+
+    /// Removes the entries farthest from the centroid of the node, as many as
+    /// `Params::reinsertion_fraction` allows while keeping the node at least minimally filled.
+    fn take_farthest_entries(&mut self, node_index: usize) -> Vec<Entry> {
+        let node = &self.nodes[node_index];
+        let number_of_entries = node.immed_children();
+        let count: f64 = cast(number_of_entries).unwrap();
+        let count: usize = cast((count * self.params.reinsertion_fraction).floor()).unwrap_or(0);
+        let count = count.min(number_of_entries.saturating_sub(self.params.min_number_of_elements));
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let entries = if node.is_leaf() {
+            // reshape keeps the points of a leaf sorted by descending distance from the centroid
+            let mut points = node.points().clone();
+            let rest = points.split_off(count);
+            self.nodes[node_index].set_points(rest);
+            points.into_iter().map(Entry::Point).collect()
+        } else {
+            let mut children = node.children().clone();
+            children.sort_by_key(|child_index| {
+                let child = &self.nodes[*child_index];
+                -OrderedFloat(self.distance(&node.sphere.center, &child.sphere.center))
+            });
+            let rest = children.split_off(count);
+            self.nodes[node_index].set_children(rest);
+            children.into_iter().map(Entry::Node).collect()
+        };
+        self.update_height(node_index);
+        self.reshape(node_index);
+        entries
+    }
+
+    /// Links a freshly split sibling to the parent of the node, growing a new root if needed.
+    fn attach_sibling(&mut self, node_index: usize, sibling_index: usize) {
+        let parent_index = self.nodes[node_index].parent_index;
+        if parent_index == usize::MAX {
+            let root = Node::new_node(vec![node_index, sibling_index], 0);
+            let root_index = self.add_node(root);
+            self.nodes[node_index].parent_index = root_index;
+            self.nodes[sibling_index].parent_index = root_index;
+            self.update_height(root_index);
+            self.reshape(root_index);
+            self.root_index = root_index;
+        } else {
+            self.nodes[parent_index].children_mut().push(sibling_index);
+            self.nodes[sibling_index].parent_index = parent_index;
+        }
+    }
+
+    pub(crate) fn update_height(&mut self, node_index: usize) {
+        let node = &self.nodes[node_index];
+        if node.is_leaf() {
+            return;
+        }
+        let height = 1 + node
+            .children()
+            .iter()
+            .map(|child_index| self.nodes[*child_index].height)
+            .max()
+            .unwrap_or(0);
+        self.nodes[node_index].height = height;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Params;
+
+    #[test]
+    pub fn test_insert() {
+        let points = vec![vec![0., 0.], vec![1., 1.], vec![2., 2.]];
+        let mut tree = SRTree::euclidean(&points).expect("Failed to build SRTree");
+        let index = tree.insert(vec![10., 10.]);
+        assert_eq!(index, 3);
+        assert_eq!(tree.num_points(), 4);
+
+        let (indices, distances) = tree.query(&[9., 9.], 1);
+        assert_eq!(indices, vec![3]);
+        assert_eq!(distances, vec![2_f64.sqrt()]);
+        assert_eq!(tree.query_radius(&[10., 10.], 0.), vec![3]);
+
+        assert!(matches!(
+            tree.try_insert(vec![f64::NAN, 0.]),
+            Err(Error::NonFiniteCoordinate { row: 4, column: 0 })
+        ));
+        assert!(matches!(
+            tree.try_insert(vec![0.]),
+            Err(Error::DimensionMismatch {
+                row: 4,
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert_eq!(tree.try_insert(vec![5., 5.]).unwrap(), 4);
+        assert_eq!(tree.num_points(), 5);
+        assert_eq!(tree.validate(), Ok(()));
+    }
+
+    #[test]
+    pub fn test_take_farthest_entries() {
+        let points: Vec<Vec<f64>> = (0..10).map(|i| vec![f64::from(i), 0.]).collect();
+        let params = Params::new(2, 5)
+            .unwrap()
+            .with_reinsertion_fraction(0.4)
+            .unwrap();
+        let mut tree = SRTree::euclidean_with_params(&points, params).unwrap();
+        let leaf_index = tree.points[0].parent_index;
+        let point_index = tree.push_point(vec![-10., 0.]);
+        tree.nodes[leaf_index].points_mut().push(point_index);
+        tree.reshape(leaf_index);
+
+        let entries = tree.take_farthest_entries(leaf_index);
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(entries[0], Entry::Point(10)));
+        assert!(matches!(entries[1], Entry::Point(4)));
+        assert_eq!(tree.points_of(leaf_index).len(), 4);
+        assert_eq!(tree.nodes[leaf_index].rect.low, vec![0., 0.]);
+    }
+
+    #[test]
+    pub fn test_insert_with_splits() {
+        let points = vec![vec![0., 0.]];
+        let mut tree = SRTree::euclidean_with_params(&points, Params::new(2, 5).unwrap())
+            .expect("Failed to build SRTree");
+        for i in 1..100 {
+            tree.insert(vec![f64::from(i), f64::from(i)]);
+        }
+        assert_eq!(tree.num_points(), 100);
+        assert!(tree.height() > 2);
+        for node in &tree.nodes {
+            assert!(node.immed_children() <= 5);
+        }
+
+        let (indices, _) = tree.query(&[50., 50.], 3);
+        assert_eq!(indices[0], 50);
+        let mut indices = tree.query_radius(&[50., 50.], 2_f64.sqrt());
+        indices.sort_unstable();
+        assert_eq!(indices, vec![49, 50, 51]);
+    }
+}
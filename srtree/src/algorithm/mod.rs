@@ -1,3 +1,13 @@
+pub(crate) mod access;
+pub mod batch;
 pub mod bulk_loading;
+pub mod deletion;
+pub mod insertion;
+pub mod nearest;
+#[cfg(feature = "rayon")]
+pub mod par_bulk_loading;
 pub mod query;
 pub mod query_radius;
+pub mod split;
+pub mod update;
+pub mod validation;
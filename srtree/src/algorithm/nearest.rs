@@ -0,0 +1,165 @@
+use crate::SRTree;
+use crate::{measure::distance::Metric, shape::point::Point};
+use ordered_float::{Float, OrderedFloat};
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+enum Entry {
+    Node(usize),
+    Point(usize),
+}
+
+struct Candidate<T>
+where
+    T: Float,
+{
+    distance: OrderedFloat<T>,
+    entry: Entry,
+}
+
+impl<T> Ord for Candidate<T>
+where
+    T: Float,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reversed, so that the binary heap pops the closest candidate first
+        other.distance.cmp(&self.distance)
+    }
+}
+
+impl<T> PartialOrd for Candidate<T>
+where
+    T: Float,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Eq for Candidate<T> where T: Float {}
+
+impl<T> PartialEq for Candidate<T>
+where
+    T: Float,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.distance.eq(&other.distance)
+    }
+}
+
+/// Iterator over the points of an [`SRTree`] in ascending order of distance from a query point.
+///
+/// Created by [`SRTree::nearest_iter`].
+pub struct NearestIter<'a, T, M>
+where
+    T: Float,
+{
+    tree: &'a SRTree<T, M>,
+    point: Point<T>,
+    candidates: BinaryHeap<Candidate<T>>,
+}
+
+impl<T, M> Iterator for NearestIter<'_, T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(candidate) = self.candidates.pop() {
+            match candidate.entry {
+                Entry::Point(point_index) => {
+                    return Some((point_index, candidate.distance.into_inner()));
+                }
+                Entry::Node(node_index) => self.expand(node_index),
+            }
+        }
+        None
+    }
+}
+
+impl<T, M> NearestIter<'_, T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    fn expand(&mut self, node_index: usize) {
+        let tree = self.tree;
+        let node = &tree.nodes[node_index];
+        if node.is_leaf() {
+            for point_index in node.points() {
+                let distance = tree
+                    .metric
+                    .distance(&self.point.coords, tree.coords_of(*point_index));
+                self.candidates.push(Candidate {
+                    distance: OrderedFloat(distance),
+                    entry: Entry::Point(*point_index),
+                });
+            }
+        } else {
+            for child_index in node.children() {
+                let child = &tree.nodes[*child_index];
+                self.candidates.push(Candidate {
+                    distance: OrderedFloat(tree.point_to_node_min_distance(&self.point, child)),
+                    entry: Entry::Node(*child_index),
+                });
+            }
+        }
+    }
+}
+
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    /// Returns a lazy iterator over `(point_index, distance)` pairs, from the closest point to
+    /// the farthest one.
+    ///
+    /// Nodes are expanded best-first, only when the next closest candidate is needed.
+    pub fn nearest_iter(&self, point_coords: &[T]) -> NearestIter<'_, T, M> {
+        let mut candidates = BinaryHeap::new();
+        if self.root_index != usize::MAX {
+            candidates.push(Candidate {
+                distance: OrderedFloat(T::zero()),
+                entry: Entry::Node(self.root_index),
+            });
+        }
+        NearestIter {
+            tree: self,
+            point: Point::with_coords(point_coords.to_vec()),
+            candidates,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Params, SRTree};
+
+    #[test]
+    pub fn test_nearest_iter() {
+        let points = vec![
+            vec![0.0, 0.0],
+            vec![1.0, 1.0],
+            vec![2.0, 2.0],
+            vec![3.0, 3.0],
+            vec![4.0, 4.0],
+            vec![5.0, 5.0],
+            vec![6.0, 6.0],
+            vec![7.0, 7.0],
+            vec![8.0, 8.0],
+            vec![9.0, 9.0],
+        ];
+        let tree = SRTree::euclidean_with_params(&points, Params::new(2, 5).unwrap())
+            .expect("Failed to build SRTree");
+        let neighbors: Vec<(usize, f64)> = tree.nearest_iter(&[9.0, 9.0]).take(3).collect();
+        assert_eq!(
+            neighbors,
+            vec![(9, 0.0), (8, 2_f64.sqrt()), (7, 8_f64.sqrt())]
+        );
+
+        let indices: Vec<usize> = tree.nearest_iter(&[0.0, 0.0]).map(|(i, _)| i).collect();
+        assert_eq!(indices, (0..10).collect::<Vec<usize>>());
+    }
+}
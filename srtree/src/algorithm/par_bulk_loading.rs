@@ -0,0 +1,155 @@
+use crate::{measure::distance::Metric, node::Node, SRTree};
+use ordered_float::Float;
+use rayon::prelude::*;
+
+/// Nodes of a subtree built apart from the tree, indexed from zero, with its root last.
+struct Subtree<T> {
+    nodes: Vec<Node<T>>,
+    /// (point index, distance from the centroid of its leaf, leaf index)
+    points: Vec<(usize, T, usize)>,
+}
+
+impl<T> Subtree<T>
+where
+    T: Float + Send + Sync,
+{
+    /// Moves the nodes of another subtree after the nodes of this one, shifting their indices,
+    /// and returns the new index of its root.
+    fn append(&mut self, other: Subtree<T>) -> usize {
+        let offset = self.nodes.len();
+        for mut node in other.nodes {
+            if !node.is_leaf() {
+                for child_index in node.children_mut() {
+                    *child_index += offset;
+                }
+            }
+            if node.parent_index != usize::MAX {
+                node.parent_index += offset;
+            }
+            node.sphere.center.index += offset;
+            self.nodes.push(node);
+        }
+        self.points.extend(
+            other
+                .points
+                .into_iter()
+                .map(|(point_index, radius, leaf_index)| {
+                    (point_index, radius, leaf_index + offset)
+                }),
+        );
+        self.nodes.len() - 1
+    }
+}
+
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T> + Sync,
+{
+    /// Bulk-loads the given points like [`SRTree::bulk_load`], building the subtrees of every
+    /// node in parallel. Nodes end up in the same order as with sequential bulk-loading.
+    pub fn par_bulk_load(&mut self, point_indices: Vec<usize>) -> usize {
+        if point_indices.is_empty() {
+            return usize::MAX;
+        }
+
+        let height = self.bulk_load_height(point_indices.len());
+        let subtree = self.build_subtree(point_indices, height);
+        let mut tree = Subtree {
+            nodes: std::mem::take(&mut self.nodes),
+            points: Vec::new(),
+        };
+        let root_index = tree.append(subtree);
+        for (point_index, radius, leaf_index) in tree.points {
+            self.points[point_index].radius = radius;
+            self.points[point_index].parent_index = leaf_index;
+        }
+        self.nodes = tree.nodes;
+        root_index
+    }
+
+    fn build_subtree(&self, point_indices: Vec<usize>, height: usize) -> Subtree<T> {
+        if height == 1 {
+            let (rect, sphere, points) = self.leaf_shape(&point_indices, 0);
+            let mut leaf = Node::new_leaf(points.iter().map(|(_, index)| *index).collect());
+            leaf.rect = rect;
+            leaf.sphere = sphere;
+            return Subtree {
+                nodes: vec![leaf],
+                points: points
+                    .into_iter()
+                    .map(|(radius, point_index)| (point_index, radius, 0))
+                    .collect(),
+            };
+        }
+
+        let subtrees: Vec<Subtree<T>> = self
+            .create_entries(point_indices, height)
+            .into_par_iter()
+            .map(|group| self.build_subtree(group, height - 1))
+            .collect();
+        let mut tree = Subtree {
+            nodes: Vec::new(),
+            points: Vec::new(),
+        };
+        let children: Vec<usize> = subtrees
+            .into_iter()
+            .map(|subtree| tree.append(subtree))
+            .collect();
+
+        let root_index = tree.nodes.len();
+        let child_nodes: Vec<&Node<T>> = children
+            .iter()
+            .map(|child_index| &tree.nodes[*child_index])
+            .collect();
+        let (rect, sphere) = self.node_shape(&child_nodes, root_index);
+        let mut root = Node::new_node(children.clone(), height);
+        root.rect = rect;
+        root.sphere = sphere;
+        tree.nodes.push(root);
+
+        for child_index in children {
+            tree.nodes[child_index].parent_index = root_index;
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Euclidean, Params, SRTree};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    pub fn test_par_bulk_load() {
+        let mut rng = StdRng::from_seed(*b"PiH6Xi3GBBXhTK6UsXJYngHaF3fx4aYS");
+        let pts: Vec<Vec<f64>> = (0..5000)
+            .map(|_| (0..4).map(|_| rng.gen::<f64>()).collect())
+            .collect();
+        let params = Params::new(4, 10).unwrap();
+        let tree = SRTree::new(&pts, params, Euclidean::default()).unwrap();
+        let par_tree = SRTree::par_new(&pts, params, Euclidean::default()).unwrap();
+
+        assert_eq!(tree.root_index, par_tree.root_index);
+        assert_eq!(tree.num_nodes(), par_tree.num_nodes());
+        for (node, par_node) in tree.nodes.iter().zip(&par_tree.nodes) {
+            assert_eq!(node.is_leaf(), par_node.is_leaf());
+            if node.is_leaf() {
+                assert_eq!(node.points(), par_node.points());
+            } else {
+                assert_eq!(node.children(), par_node.children());
+            }
+            assert_eq!(node.height, par_node.height);
+            assert_eq!(node.parent_index, par_node.parent_index);
+            assert_eq!(node.rect.low, par_node.rect.low);
+            assert_eq!(node.rect.high, par_node.rect.high);
+            assert_eq!(node.sphere.center.coords, par_node.sphere.center.coords);
+            assert_eq!(node.sphere.center.index, par_node.sphere.center.index);
+            assert_eq!(node.sphere.radius, par_node.sphere.radius);
+        }
+        for (point, par_point) in tree.points.iter().zip(&par_tree.points) {
+            assert_eq!(point.radius, par_point.radius);
+            assert_eq!(point.parent_index, par_point.parent_index);
+        }
+    }
+}
@@ -1,5 +1,5 @@
-use crate::SRTree;
-use crate::{measure::distance::Metric, shape::point::Point};
+use super::access::TreeAccess;
+use crate::{error::Error, measure::distance::Metric, stats::QueryStats, SRTree};
 use ordered_float::{Float, OrderedFloat};
 use std::{cmp::Ordering, collections::BinaryHeap};
 
@@ -27,7 +27,6 @@ impl<T> Ord for Neighbor<T>
 where
     T: Float,
 {
-    #[must_use]
     fn cmp(&self, other: &Self) -> Ordering {
         self.distance.cmp(&other.distance)
     }
@@ -37,7 +36,6 @@ impl<T> PartialOrd for Neighbor<T>
 where
     T: Float,
 {
-    #[must_use]
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
@@ -49,7 +47,6 @@ impl<T> PartialEq for Neighbor<T>
 where
     T: Float,
 {
-    #[must_use]
     fn eq(&self, other: &Self) -> bool {
         self.distance.eq(&other.distance)
     }
@@ -60,82 +57,157 @@ where
     T: Float + Send + Sync,
     M: Metric<T>,
 {
+    /// Finds the `k` nearest neighbors and returns their indices and distances, from the
+    /// closest one.
+    ///
+    /// Returns no neighbor if `k` is 0, and every point of the tree if `k` exceeds their number.
+    /// The dimension of `point_coords` is not checked; see [`SRTree::try_query`].
     pub fn query(&self, point_coords: &[T], k: usize) -> (Vec<usize>, Vec<T>) {
-        let mut neighbors = BinaryHeap::new();
-        self.search(
-            &Point::with_coords(point_coords.to_vec()),
-            self.root_index,
-            k,
-            &mut neighbors,
-        );
-        let neighbors = neighbors.into_sorted_vec();
-
-        let indices = neighbors.iter().map(|n| n.point_index).collect();
-        let distances = neighbors.iter().map(|n| n.distance.into_inner()).collect();
-        (indices, distances)
+        self.query_filtered(point_coords, k, |_| true)
     }
 
-    fn search(
+    /// Runs [`SRTree::query`] after checking the dimension of `point_coords`.
+    ///
+    /// # Errors
+    /// * `Error::QueryDimensionMismatch` if `point_coords` does not have the dimension of the
+    ///   tree.
+    pub fn try_query(&self, point_coords: &[T], k: usize) -> Result<(Vec<usize>, Vec<T>), Error> {
+        self.check_query_dimension(point_coords)?;
+        Ok(self.query(point_coords, k))
+    }
+
+    /// Finds the `k` nearest neighbors among the points for which `filter` returns `true`.
+    ///
+    /// The filter is applied while searching, so up to `k` neighbors are returned even if
+    /// most of the nearest points are rejected.
+    pub fn query_filtered<F>(&self, point_coords: &[T], k: usize, filter: F) -> (Vec<usize>, Vec<T>)
+    where
+        F: Fn(usize) -> bool,
+    {
+        query_tree(self, point_coords, k, &filter, &mut QueryStats::default())
+    }
+
+    /// Runs [`SRTree::query`] and also returns counts of the nodes and points the search went
+    /// through.
+    pub fn query_with_stats(
         &self,
-        point: &Point<T>,
-        node_index: usize,
+        point_coords: &[T],
         k: usize,
-        neighbors: &mut BinaryHeap<Neighbor<T>>,
-    ) {
-        let node = &self.nodes[node_index];
-
-        let mut kth_distance = OrderedFloat(T::infinity());
-        if node.is_leaf() {
-            let distance_to_center = self.distance(point, &node.sphere.center);
-            for candidate_index in node.points() {
-                let candidate = &self.points[*candidate_index];
-                if neighbors.len() == k {
-                    kth_distance = neighbors.peek().unwrap().distance;
-                }
-
-                // ball-bound pruning
-                let ball_bound = (distance_to_center - candidate.radius).max(T::zero());
-                if OrderedFloat(ball_bound) > kth_distance {
-                    break;
-                }
-
-                let neighbor_distance = OrderedFloat(self.distance(point, candidate));
-                if neighbors.len() < k {
-                    neighbors.push(Neighbor::new(neighbor_distance, candidate.index));
-                } else if neighbor_distance < kth_distance {
-                    neighbors.pop();
-                    neighbors.push(Neighbor::new(neighbor_distance, candidate.index));
-                }
+    ) -> (Vec<usize>, Vec<T>, QueryStats) {
+        let mut stats = QueryStats::default();
+        let (indices, distances) = query_tree(self, point_coords, k, &|_| true, &mut stats);
+        (indices, distances, stats)
+    }
+}
+
+/// Finds the `k` nearest neighbors accepted by `filter` in any tree.
+pub(crate) fn query_tree<T, A, F>(
+    tree: &A,
+    point_coords: &[T],
+    k: usize,
+    filter: &F,
+    stats: &mut QueryStats,
+) -> (Vec<usize>, Vec<T>)
+where
+    T: Float,
+    A: TreeAccess<T>,
+    F: Fn(usize) -> bool,
+{
+    let mut neighbors = BinaryHeap::new();
+    if tree.root() == usize::MAX || k == 0 {
+        return (Vec::new(), Vec::new());
+    }
+    search(
+        tree,
+        point_coords,
+        tree.root(),
+        k,
+        filter,
+        &mut neighbors,
+        stats,
+    );
+    let neighbors = neighbors.into_sorted_vec();
+
+    let indices = neighbors.iter().map(|n| n.point_index).collect();
+    let distances = neighbors.iter().map(|n| n.distance.into_inner()).collect();
+    (indices, distances)
+}
+
+fn search<T, A, F>(
+    tree: &A,
+    point: &[T],
+    node_index: usize,
+    k: usize,
+    filter: &F,
+    neighbors: &mut BinaryHeap<Neighbor<T>>,
+    stats: &mut QueryStats,
+) where
+    T: Float,
+    A: TreeAccess<T>,
+    F: Fn(usize) -> bool,
+{
+    let metric = tree.metric();
+    let mut kth_distance = OrderedFloat(T::infinity());
+    stats.inc_visited_nodes(tree.is_leaf(node_index));
+    if tree.is_leaf(node_index) {
+        let distance_to_center = metric.distance(point, tree.center(node_index));
+        for position in 0..tree.num_entries(node_index) {
+            let candidate_index = tree.entry(node_index, position);
+            if neighbors.len() == k {
+                kth_distance = neighbors.peek().unwrap().distance;
+            }
+
+            // ball-bound pruning
+            stats.inc_visited_points();
+            let ball_bound =
+                (distance_to_center - tree.point_radius(candidate_index)).max(T::zero());
+            if OrderedFloat(ball_bound) > kth_distance {
+                break;
             }
-        } else {
-            let mut to_visit = Vec::new();
-            for child_index in node.children() {
-                let child = &self.nodes[*child_index];
-                let distance = OrderedFloat(self.point_to_node_min_distance(point, child));
-                to_visit.push((distance, *child_index));
+
+            if !filter(candidate_index) {
+                continue;
             }
-            to_visit.sort();
 
-            for (child_distance, child_index) in to_visit {
-                // if k neighbors were already sampled, then the target distance is kth closest distance:
-                if neighbors.len() == k {
-                    kth_distance = neighbors.peek().unwrap().distance;
-                }
+            stats.inc_compared_points();
+            let neighbor_distance =
+                OrderedFloat(metric.distance(point, tree.coords(candidate_index)));
+            if neighbors.len() < k {
+                neighbors.push(Neighbor::new(neighbor_distance, candidate_index));
+            } else if neighbor_distance < kth_distance {
+                neighbors.pop();
+                neighbors.push(Neighbor::new(neighbor_distance, candidate_index));
+            }
+        }
+    } else {
+        let mut to_visit = Vec::new();
+        for position in 0..tree.num_entries(node_index) {
+            let child_index = tree.entry(node_index, position);
+            stats.inc_compared_nodes(tree.is_leaf(child_index));
+            let distance = OrderedFloat(tree.min_distance(point, child_index));
+            to_visit.push((distance, child_index));
+        }
+        to_visit.sort();
 
-                // search pruning: don't visit nodes with min_distance bigger than kth distance
-                if child_distance > kth_distance {
-                    break;
-                }
+        for (child_distance, child_index) in to_visit {
+            // if k neighbors were already sampled, then the target distance is kth closest distance:
+            if neighbors.len() == k {
+                kth_distance = neighbors.peek().unwrap().distance;
+            }
 
-                self.search(point, child_index, k, neighbors);
+            // search pruning: don't visit nodes with min_distance bigger than kth distance
+            if child_distance > kth_distance {
+                break;
             }
+
+            search(tree, point, child_index, k, filter, neighbors, stats);
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Params, SRTree};
+    use crate::{Error, Params, SRTree};
 
     #[test]
     pub fn test_query() {
@@ -157,4 +229,40 @@ mod tests {
         assert_eq!(indices, vec![0, 1, 2]);
         assert_eq!(distances, vec![0.0, 2_f64.sqrt(), 8_f64.sqrt()]);
     }
+
+    #[test]
+    pub fn test_query_filtered() {
+        let points: Vec<Vec<f64>> = (0..100).map(|i| vec![f64::from(i), 0.0]).collect();
+        let tree = SRTree::euclidean_with_params(&points, Params::new(2, 5).unwrap())
+            .expect("Failed to build SRTree");
+        let (indices, distances) = tree.query_filtered(&[0.0, 0.0], 3, |i| i % 10 == 9);
+        assert_eq!(indices, vec![9, 19, 29]);
+        assert_eq!(distances, vec![9.0, 19.0, 29.0]);
+
+        let (indices, _) = tree.query_filtered(&[0.0, 0.0], 3, |i| i == 50);
+        assert_eq!(indices, vec![50]);
+        let (indices, _) = tree.query_filtered(&[0.0, 0.0], 3, |_| false);
+        assert!(indices.is_empty());
+    }
+
+    #[test]
+    pub fn test_try_query() {
+        let points: Vec<Vec<f64>> = (0..10).map(|i| vec![f64::from(i), 0.0]).collect();
+        let tree = SRTree::euclidean_with_params(&points, Params::new(2, 5).unwrap())
+            .expect("Failed to build SRTree");
+        let (indices, _) = tree.try_query(&[0.0, 0.0], 2).unwrap();
+        assert_eq!(indices, vec![0, 1]);
+        assert!(matches!(
+            tree.try_query(&[0.0, 0.0, 0.0], 2),
+            Err(Error::QueryDimensionMismatch {
+                expected: 2,
+                found: 3
+            })
+        ));
+
+        let (indices, distances) = tree.query(&[0.0, 0.0], 0);
+        assert!(indices.is_empty() && distances.is_empty());
+        let (indices, _) = tree.query(&[0.0, 0.0], 100);
+        assert_eq!(indices, (0..10).collect::<Vec<_>>());
+    }
 }
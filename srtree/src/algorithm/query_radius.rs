@@ -1,5 +1,6 @@
+use super::access::TreeAccess;
+use crate::error::Error;
 use crate::measure::distance::Metric;
-use crate::shape::point::Point;
 use crate::SRTree;
 use ordered_float::Float;
 
@@ -8,58 +9,79 @@ where
     T: Float + Send + Sync,
     M: Metric<T>,
 {
+    /// Returns the indices of the points within `radius`, in no particular order.
+    ///
+    /// The dimension of `point_coords` is not checked; see [`SRTree::try_query_radius`].
     pub fn query_radius(&self, point_coords: &[T], radius: T) -> Vec<usize> {
-        let mut neighbors = Vec::new();
-        self.search_radius(
-            self.root_index,
-            &Point::with_coords(point_coords.to_vec()),
-            radius,
-            &mut neighbors,
-        );
-        neighbors
+        query_radius_tree(self, point_coords, radius)
     }
 
-    fn search_radius(
-        &self,
-        node_index: usize,
-        point: &Point<T>,
-        radius: T,
-        neighbors: &mut Vec<usize>,
-    ) {
-        let node = &self.nodes[node_index];
-        if node.is_leaf() {
-            let distance_to_center = self.distance(point, &node.sphere.center);
-            for candidate_index in node.points() {
-                let candidate = &self.points[*candidate_index];
+    /// Runs [`SRTree::query_radius`] after checking the dimension of `point_coords`.
+    ///
+    /// # Errors
+    /// * `Error::QueryDimensionMismatch` if `point_coords` does not have the dimension of the
+    ///   tree.
+    pub fn try_query_radius(&self, point_coords: &[T], radius: T) -> Result<Vec<usize>, Error> {
+        self.check_query_dimension(point_coords)?;
+        Ok(self.query_radius(point_coords, radius))
+    }
+}
+
+/// Finds the points within `radius` in any tree.
+pub(crate) fn query_radius_tree<T, A>(tree: &A, point_coords: &[T], radius: T) -> Vec<usize>
+where
+    T: Float,
+    A: TreeAccess<T>,
+{
+    let mut neighbors = Vec::new();
+    if tree.root() == usize::MAX {
+        return neighbors;
+    }
+    search_radius(tree, tree.root(), point_coords, radius, &mut neighbors);
+    neighbors
+}
 
-                // ball-bound pruning
-                let ball_bound = (distance_to_center - candidate.radius).max(T::zero());
-                if ball_bound > radius {
-                    break;
-                }
+fn search_radius<T, A>(
+    tree: &A,
+    node_index: usize,
+    point: &[T],
+    radius: T,
+    neighbors: &mut Vec<usize>,
+) where
+    T: Float,
+    A: TreeAccess<T>,
+{
+    let metric = tree.metric();
+    if tree.is_leaf(node_index) {
+        let distance_to_center = metric.distance(point, tree.center(node_index));
+        for position in 0..tree.num_entries(node_index) {
+            let candidate_index = tree.entry(node_index, position);
 
-                let neighbor_distance = self.distance(point, candidate);
-                if neighbor_distance <= radius {
-                    neighbors.push(candidate.index);
-                }
+            // ball-bound pruning
+            let ball_bound =
+                (distance_to_center - tree.point_radius(candidate_index)).max(T::zero());
+            if ball_bound > radius {
+                break;
+            }
+
+            let neighbor_distance = metric.distance(point, tree.coords(candidate_index));
+            if neighbor_distance <= radius {
+                neighbors.push(candidate_index);
+            }
+        }
+    } else {
+        for position in 0..tree.num_entries(node_index) {
+            let child_index = tree.entry(node_index, position);
+            if tree.min_distance(point, child_index) <= radius {
+                search_radius(tree, child_index, point, radius, neighbors);
             }
-        } else {
-            node.children()
-                .iter()
-                .filter(|child| {
-                    let child = &self.nodes[**child];
-                    self.point_to_node_min_distance(point, child) <= radius
-                })
-                .for_each(|child_index| {
-                    self.search_radius(*child_index, point, radius, neighbors);
-                });
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Params, SRTree};
+    use crate::{Error, Params, SRTree};
 
     #[test]
     pub fn test_query() {
@@ -81,4 +103,20 @@ mod tests {
         indices.sort();
         assert_eq!(indices, vec![0, 1, 2]);
     }
+
+    #[test]
+    pub fn test_try_query_radius() {
+        let points: Vec<Vec<f64>> = (0..10).map(|i| vec![f64::from(i), 0.0]).collect();
+        let tree = SRTree::euclidean(&points).expect("Failed to build SRTree");
+        let mut indices = tree.try_query_radius(&[0.0, 0.0], 1.0).unwrap();
+        indices.sort_unstable();
+        assert_eq!(indices, vec![0, 1]);
+        assert!(matches!(
+            tree.try_query_radius(&[0.0], 1.0),
+            Err(Error::QueryDimensionMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
 }
@@ -0,0 +1,169 @@
+use crate::{measure::distance::Metric, node::Node, SRTree};
+use ordered_float::{Float, OrderedFloat};
+
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    /// Splits an overflowing node in two and returns the index of the new sibling node.
+    ///
+    /// The entries are partitioned by [`SRTree::split_entries`]: the first group stays in the
+    /// node and the second one moves into the sibling. The sibling is not linked to the parent
+    /// of the node; this is left to the caller.
+    pub fn split(&mut self, node_index: usize) -> usize {
+        let (left, right) = self.split_entries(node_index);
+        let sibling_index = if self.nodes[node_index].is_leaf() {
+            self.nodes[node_index].set_points(left);
+            self.add_node(Node::new_leaf(right))
+        } else {
+            self.nodes[node_index].set_children(left);
+            let sibling_index = self.add_node(Node::new_node(right.clone(), 0));
+            for child_index in right {
+                self.nodes[child_index].parent_index = sibling_index;
+            }
+            sibling_index
+        };
+        self.update_height(node_index);
+        self.update_height(sibling_index);
+        self.reshape(node_index);
+        self.reshape(sibling_index);
+        sibling_index
+    }
+
+    /// Partitions the entries of a node (points of a leaf, children of an internal node) in two.
+    ///
+    /// Entries are sorted along the axis with the highest variance of their centroids, and the
+    /// split position minimizing the sum of the variances of both groups along that axis is
+    /// chosen among the positions leaving between `min_number_of_elements` and
+    /// `max_number_of_elements` entries on each side.
+    #[must_use]
+    pub fn split_entries(&self, node_index: usize) -> (Vec<usize>, Vec<usize>) {
+        let node = &self.nodes[node_index];
+        let (mut entries, variances) = if node.is_leaf() {
+            let points = node.points().clone();
+            let variances = self.calculate_points_variance(&points);
+            (points, variances)
+        } else {
+            let children = node.children().clone();
+            let variances = self.calculate_nodes_variance(&children);
+            (children, variances)
+        };
+        let split_dim = variances
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, variance)| OrderedFloat(**variance))
+            .map_or(0, |(i, _)| i);
+
+        let coord_of = |entry: &usize| {
+            if node.is_leaf() {
+                self.coords_of(*entry)[split_dim]
+            } else {
+                self.nodes[*entry].sphere.center.coords[split_dim]
+            }
+        };
+        entries.sort_by_key(|entry| OrderedFloat(coord_of(entry)));
+        let coords: Vec<T> = entries.iter().map(coord_of).collect();
+        let position = split_position(
+            &coords,
+            self.params.min_number_of_elements,
+            self.params.max_number_of_elements,
+        );
+        let right = entries.split_off(position);
+        (entries, right)
+    }
+}
+
+/// Finds the position in sorted coordinates that minimizes the total sum of squared deviations
+/// of both sides, keeping the size of each side within `[min, max]` whenever possible.
+fn split_position<T>(coords: &[T], min: usize, max: usize) -> usize
+where
+    T: Float,
+{
+    let n = coords.len();
+    let lower = min.max(n.saturating_sub(max)).max(1);
+    let upper = n.saturating_sub(min).min(max).min(n.saturating_sub(1));
+    if lower > upper {
+        return n / 2;
+    }
+
+    let mut sum = vec![T::zero(); n + 1];
+    let mut sum_sq = vec![T::zero(); n + 1];
+    for (i, coord) in coords.iter().enumerate() {
+        sum[i + 1] = sum[i] + *coord;
+        sum_sq[i + 1] = sum_sq[i] + *coord * *coord;
+    }
+    let deviation = |from: usize, to: usize| {
+        let count = T::from(to - from).unwrap();
+        let s = sum[to] - sum[from];
+        (sum_sq[to] - sum_sq[from]) - s * s / count
+    };
+
+    (lower..=upper)
+        .min_by_key(|position| {
+            let total = deviation(0, *position) + deviation(*position, n);
+            (OrderedFloat(total), position.abs_diff(n / 2))
+        })
+        .unwrap_or(n / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Params, SRTree};
+
+    #[test]
+    pub fn test_split_position() {
+        let coords = [0., 1., 2., 10., 11., 12., 13.];
+        assert_eq!(split_position(&coords, 1, 6), 3);
+        assert_eq!(split_position(&[0., 1., 2., 10.], 2, 3), 2);
+        assert_eq!(split_position(&[0., 0., 0., 0.], 1, 3), 2);
+    }
+
+    #[test]
+    pub fn test_split_leaf() {
+        let points = vec![
+            vec![0., 5.],
+            vec![1., 0.],
+            vec![2., 4.],
+            vec![10., 1.],
+            vec![11., 3.],
+            vec![12., 2.],
+        ];
+        let mut tree = SRTree::euclidean_with_params(&points, Params::new(2, 6).unwrap())
+            .expect("Failed to build SRTree");
+        let (mut left, mut right) = tree.split_entries(0);
+        left.sort_unstable();
+        right.sort_unstable();
+        assert_eq!(left, vec![0, 1, 2]);
+        assert_eq!(right, vec![3, 4, 5]);
+
+        let sibling_index = tree.split(0);
+        assert_eq!(sibling_index, 1);
+        assert_eq!(tree.nodes[0].rect.high, vec![2., 5.]);
+        assert_eq!(tree.nodes[1].rect.low, vec![10., 1.]);
+        assert!(tree
+            .points_of(1)
+            .iter()
+            .all(|p| tree.points[*p].parent_index == 1));
+    }
+
+    #[test]
+    pub fn test_split_node() {
+        let points: Vec<Vec<f64>> = (0..60).map(|i| vec![0., f64::from(i)]).collect();
+        let mut tree = SRTree::euclidean_with_params(&points, Params::new(4, 8).unwrap())
+            .expect("Failed to build SRTree");
+        let root_index = tree.root_index;
+        let children = tree.nodes[root_index].children().clone();
+        let (left, right) = tree.split_entries(root_index);
+        assert_eq!(children.len(), 8);
+        assert_eq!((left.len(), right.len()), (4, 4));
+        assert_eq!(left.len() + right.len(), children.len());
+
+        let sibling_index = tree.split(root_index);
+        for child_index in tree.nodes[sibling_index].children() {
+            assert_eq!(tree.nodes[*child_index].parent_index, sibling_index);
+            assert!(tree.nodes[*child_index].rect.low[1] > tree.nodes[root_index].rect.high[1]);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{error::Error, measure::distance::Metric, SRTree};
+use ordered_float::Float;
+
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    /// Moves the point with the given index to new coordinates, keeping its index.
+    ///
+    /// If the new location stays inside the bounding rectangle and sphere of its leaf,
+    /// only the leaf and its ancestors are reshaped. Otherwise, the point is removed
+    /// and inserted again. Returns `false` if there is no such point in the tree.
+    ///
+    /// # Panics
+    /// * If the dimension of `new_coords` differs from the dimension of the tree, or if
+    ///   `new_coords` has a NaN or infinite coordinate.
+    pub fn update(&mut self, point_index: usize, new_coords: Vec<T>) -> bool {
+        match self.try_update(point_index, new_coords) {
+            Ok(updated) => updated,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Runs [`SRTree::update`] after checking `new_coords`, reporting `point_index` as the row
+    /// of an error.
+    ///
+    /// # Errors
+    /// * `Error::DimensionMismatch` if the dimension of `new_coords` differs from the dimension
+    ///   of the tree.
+    /// * `Error::NonFiniteCoordinate` if `new_coords` has a NaN or infinite coordinate.
+    pub fn try_update(&mut self, point_index: usize, new_coords: Vec<T>) -> Result<bool, Error> {
+        self.check_point(point_index, &new_coords)?;
+        if !self.contains(point_index) {
+            return Ok(false);
+        }
+
+        let leaf_index = self.points[point_index].parent_index;
+        let leaf = &self.nodes[leaf_index];
+        let inside_rect = (0..self.params.dimension)
+            .all(|i| leaf.rect.low[i] <= new_coords[i] && new_coords[i] <= leaf.rect.high[i]);
+        let inside_sphere = self
+            .metric
+            .distance(&new_coords, &leaf.sphere.center.coords)
+            <= leaf.sphere.radius;
+        for (coord, new_coord) in self.coords_of_mut(point_index).iter_mut().zip(new_coords) {
+            *coord = new_coord;
+        }
+
+        if inside_rect && inside_sphere {
+            self.adjust_tree(leaf_index);
+        } else {
+            self.remove(point_index);
+            self.insert_point(point_index);
+            self.num_points += 1;
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, Params, SRTree};
+
+    #[test]
+    pub fn test_update_inside_leaf() {
+        let points = vec![vec![0., 0.], vec![1., 1.], vec![2., 2.], vec![3., 3.]];
+        let mut tree = SRTree::euclidean(&points).expect("Failed to build SRTree");
+        assert!(tree.update(1, vec![2., 1.]));
+        assert_eq!(tree.num_nodes(), 1);
+        assert_eq!(tree.num_points(), 4);
+        assert_eq!(tree.coords_of(1), [2., 1.]);
+        assert_eq!(tree.nodes[0].sphere.center.coords, vec![1.75, 1.5]);
+
+        let (indices, distances) = tree.query(&[2., 1.], 1);
+        assert_eq!(indices, vec![1]);
+        assert_eq!(distances, vec![0.]);
+        assert!(!tree.update(4, vec![0., 0.]));
+    }
+
+    #[test]
+    pub fn test_update_outside_leaf() {
+        let points: Vec<Vec<f64>> = (0..50).map(|i| vec![f64::from(i), 0.]).collect();
+        let mut tree = SRTree::euclidean_with_params(&points, Params::new(2, 5).unwrap())
+            .expect("Failed to build SRTree");
+        assert!(tree.update(0, vec![100., 0.]));
+        assert_eq!(tree.num_points(), 50);
+
+        let (indices, distances) = tree.query(&[100., 0.], 2);
+        assert_eq!(indices, vec![0, 49]);
+        assert_eq!(distances, vec![0., 51.]);
+        let (indices, _) = tree.query(&[0., 0.], 1);
+        assert_eq!(indices, vec![1]);
+
+        assert!(matches!(
+            tree.try_update(2, vec![0., f64::INFINITY]),
+            Err(Error::NonFiniteCoordinate { row: 2, column: 1 })
+        ));
+        assert!(matches!(
+            tree.try_update(2, vec![0.]),
+            Err(Error::DimensionMismatch { row: 2, .. })
+        ));
+        assert_eq!(tree.coords_of(2), [2., 0.]);
+        assert!(!tree.try_update(50, vec![0., 0.]).unwrap());
+        assert_eq!(tree.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,370 @@
+use crate::{measure::distance::Metric, SRTree};
+use ordered_float::Float;
+use std::fmt;
+
+/// A broken structural guarantee found by [`SRTree::validate`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The root index is out of range, or the root has a parent.
+    InvalidRoot,
+    /// A child index of a node is out of range.
+    NodeIndexOutOfRange {
+        node_index: usize,
+        child_index: usize,
+    },
+    /// A point index of a leaf is out of range, or the point has no row of coordinates.
+    PointIndexOutOfRange {
+        node_index: usize,
+        point_index: usize,
+    },
+    /// The rect or the sphere of a node does not have the dimension of the tree.
+    ShapeDimensionMismatch { node_index: usize },
+    /// A node is not reachable from the root.
+    UnreachableNode { node_index: usize },
+    /// A node is reachable from the root through more than one path.
+    DuplicateNode { node_index: usize },
+    /// A stored point is not in any leaf.
+    UnreachablePoint { point_index: usize },
+    /// A point is in more than one leaf, or more than once in a leaf.
+    DuplicatePoint { point_index: usize },
+    /// The parent index of a node does not refer to the node holding it.
+    NodeParentMismatch { node_index: usize },
+    /// The parent index of a point does not refer to the leaf holding it.
+    PointParentMismatch { point_index: usize },
+    /// A node other than the root holds fewer or more entries than `Params` allows.
+    FillOutOfRange { node_index: usize, entries: usize },
+    /// The height of a node is not one more than the height of its children, or a leaf does
+    /// not have height 1.
+    HeightMismatch { node_index: usize },
+    /// A point lies outside the rect of its leaf.
+    PointOutsideRect { point_index: usize },
+    /// A point lies outside the sphere of its leaf.
+    PointOutsideSphere { point_index: usize },
+    /// The radius of a point is not its distance to the centroid of its leaf, or the points of
+    /// the leaf are not sorted by decreasing radius.
+    PointRadiusMismatch { point_index: usize },
+    /// The rect of a node does not contain the rect of one of its children.
+    ChildOutsideRect { node_index: usize },
+    /// The sphere of a node does not contain the sphere or the rect of one of its children.
+    ChildOutsideSphere { node_index: usize },
+    /// The number of points in leaves differs from the count kept by the tree.
+    NumPointsMismatch { reachable: usize, counted: usize },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::InvalidRoot => write!(f, "invalid root"),
+            ValidationError::NodeIndexOutOfRange {
+                node_index,
+                child_index,
+            } => write!(
+                f,
+                "node {node_index} has a child {child_index} out of range"
+            ),
+            ValidationError::PointIndexOutOfRange {
+                node_index,
+                point_index,
+            } => write!(
+                f,
+                "node {node_index} has a point {point_index} out of range"
+            ),
+            ValidationError::ShapeDimensionMismatch { node_index } => {
+                write!(f, "node {node_index} has shapes of a wrong dimension")
+            }
+            ValidationError::UnreachableNode { node_index } => {
+                write!(f, "node {node_index} is not reachable from the root")
+            }
+            ValidationError::DuplicateNode { node_index } => {
+                write!(f, "node {node_index} is reachable more than once")
+            }
+            ValidationError::UnreachablePoint { point_index } => {
+                write!(f, "point {point_index} is not in any leaf")
+            }
+            ValidationError::DuplicatePoint { point_index } => {
+                write!(f, "point {point_index} is in more than one leaf")
+            }
+            ValidationError::NodeParentMismatch { node_index } => {
+                write!(f, "node {node_index} has a wrong parent index")
+            }
+            ValidationError::PointParentMismatch { point_index } => {
+                write!(f, "point {point_index} has a wrong parent index")
+            }
+            ValidationError::FillOutOfRange {
+                node_index,
+                entries,
+            } => write!(f, "node {node_index} has {entries} entries"),
+            ValidationError::HeightMismatch { node_index } => {
+                write!(f, "node {node_index} has a wrong height")
+            }
+            ValidationError::PointOutsideRect { point_index } => {
+                write!(f, "point {point_index} lies outside the rect of its leaf")
+            }
+            ValidationError::PointOutsideSphere { point_index } => {
+                write!(f, "point {point_index} lies outside the sphere of its leaf")
+            }
+            ValidationError::PointRadiusMismatch { point_index } => {
+                write!(f, "point {point_index} has a wrong radius")
+            }
+            ValidationError::ChildOutsideRect { node_index } => {
+                write!(f, "a child of node {node_index} lies outside its rect")
+            }
+            ValidationError::ChildOutsideSphere { node_index } => {
+                write!(f, "a child of node {node_index} lies outside its sphere")
+            }
+            ValidationError::NumPointsMismatch { reachable, counted } => write!(
+                f,
+                "{reachable} points are in leaves, but the tree counts {counted}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    /// Checks the structural guarantees of the tree, which every operation must preserve.
+    ///
+    /// Indices are checked before they are followed, so that a corrupted tree, such as one
+    /// deserialized from untrusted input, is reported instead of causing a panic. Distances are compared with a small relative tolerance for rounding errors.
+    ///
+    /// # Errors
+    /// * The first broken guarantee found, as a [`ValidationError`].
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut node_reached = vec![false; self.nodes.len()];
+        let mut point_reached = vec![false; self.points.len()];
+        if self.root_index != usize::MAX {
+            if self.root_index >= self.nodes.len()
+                || self.nodes[self.root_index].parent_index != usize::MAX
+            {
+                return Err(ValidationError::InvalidRoot);
+            }
+            let mut stack = vec![self.root_index];
+            while let Some(node_index) = stack.pop() {
+                if node_reached[node_index] {
+                    return Err(ValidationError::DuplicateNode { node_index });
+                }
+                node_reached[node_index] = true;
+                self.validate_node(node_index)?;
+                if self.nodes[node_index].is_leaf() {
+                    for point_index in self.nodes[node_index].points() {
+                        if point_reached[*point_index] {
+                            return Err(ValidationError::DuplicatePoint {
+                                point_index: *point_index,
+                            });
+                        }
+                        point_reached[*point_index] = true;
+                    }
+                } else {
+                    stack.extend(self.nodes[node_index].children());
+                }
+            }
+        }
+
+        if let Some(node_index) = node_reached.iter().position(|reached| !reached) {
+            return Err(ValidationError::UnreachableNode { node_index });
+        }
+        for (point_index, point) in self.points.iter().enumerate() {
+            if point.parent_index != usize::MAX && !point_reached[point_index] {
+                return Err(ValidationError::UnreachablePoint { point_index });
+            }
+        }
+        let reachable = point_reached.iter().filter(|reached| **reached).count();
+        if reachable != self.num_points {
+            return Err(ValidationError::NumPointsMismatch {
+                reachable,
+                counted: self.num_points,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that the indices and shapes of a reachable node can be followed.
+    fn validate_indices(&self, node_index: usize) -> Result<(), ValidationError> {
+        let node = &self.nodes[node_index];
+        let dimension = self.params.dimension;
+        if node.rect.low.len() != dimension
+            || node.rect.high.len() != dimension
+            || node.sphere.center.coords.len() != dimension
+        {
+            return Err(ValidationError::ShapeDimensionMismatch { node_index });
+        }
+        if node.is_leaf() {
+            for point_index in node.points() {
+                let in_range = self.points.get(*point_index).map_or(false, |point| {
+                    point
+                        .row
+                        .checked_add(1)
+                        .and_then(|rows| rows.checked_mul(dimension))
+                        .map_or(false, |end| end <= self.coords.len())
+                });
+                if !in_range {
+                    return Err(ValidationError::PointIndexOutOfRange {
+                        node_index,
+                        point_index: *point_index,
+                    });
+                }
+            }
+        } else if let Some(child_index) = node
+            .children()
+            .iter()
+            .find(|child_index| **child_index >= self.nodes.len())
+        {
+            return Err(ValidationError::NodeIndexOutOfRange {
+                node_index,
+                child_index: *child_index,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks a reachable node against its entries.
+    fn validate_node(&self, node_index: usize) -> Result<(), ValidationError> {
+        self.validate_indices(node_index)?;
+        let node = &self.nodes[node_index];
+        let entries = node.immed_children();
+        if node_index != self.root_index
+            && (entries < self.params.min_number_of_elements
+                || entries > self.params.max_number_of_elements)
+        {
+            return Err(ValidationError::FillOutOfRange {
+                node_index,
+                entries,
+            });
+        }
+
+        if node.is_leaf() {
+            if node.height != 1 {
+                return Err(ValidationError::HeightMismatch { node_index });
+            }
+            let mut previous_radius = T::infinity();
+            for point_index in node.points() {
+                let point_index = *point_index;
+                let point = &self.points[point_index];
+                if point.parent_index != node_index {
+                    return Err(ValidationError::PointParentMismatch { point_index });
+                }
+                let coords = self.coords_of(point_index);
+                let inside_rect = coords
+                    .iter()
+                    .zip(node.rect.low.iter().zip(&node.rect.high))
+                    .all(|(coord, (low, high))| low <= coord && coord <= high);
+                if !inside_rect {
+                    return Err(ValidationError::PointOutsideRect { point_index });
+                }
+                let distance = self.metric.distance(&node.sphere.center.coords, coords);
+                if exceeds(distance, node.sphere.radius) {
+                    return Err(ValidationError::PointOutsideSphere { point_index });
+                }
+                if exceeds(distance, point.radius)
+                    || exceeds(point.radius, distance)
+                    || point.radius > previous_radius
+                {
+                    return Err(ValidationError::PointRadiusMismatch { point_index });
+                }
+                previous_radius = point.radius;
+            }
+            return Ok(());
+        }
+
+        for child_index in node.children() {
+            let child = &self.nodes[*child_index];
+            if child.parent_index != node_index {
+                return Err(ValidationError::NodeParentMismatch {
+                    node_index: *child_index,
+                });
+            }
+            if child.height + 1 != node.height {
+                return Err(ValidationError::HeightMismatch { node_index });
+            }
+            let inside_rect = (0..self.params.dimension).all(|i| {
+                node.rect.low[i] <= child.rect.low[i] && child.rect.high[i] <= node.rect.high[i]
+            });
+            if !inside_rect {
+                return Err(ValidationError::ChildOutsideRect { node_index });
+            }
+            let max_distance = self.point_to_node_max_distance(&node.sphere.center, child);
+            if exceeds(max_distance, node.sphere.radius) {
+                return Err(ValidationError::ChildOutsideSphere { node_index });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns `true` if `value` is greater than `bound` by more than a rounding error.
+fn exceeds<T: Float>(value: T, bound: T) -> bool {
+    let tolerance = T::epsilon().sqrt() * T::one().max(bound.abs());
+    value > bound + tolerance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValidationError;
+    use crate::{Params, SRTree};
+
+    #[test]
+    pub fn test_validate() {
+        let points: Vec<Vec<f64>> = (0..200)
+            .map(|i| vec![f64::from(i % 17), f64::from(i % 13)])
+            .collect();
+        let mut tree = SRTree::euclidean_with_params(&points, Params::new(3, 8).unwrap())
+            .expect("Failed to build SRTree");
+        assert_eq!(tree.validate(), Ok(()));
+
+        for i in (0..200).step_by(3) {
+            tree.remove(i);
+        }
+        for i in 0..50 {
+            tree.insert(vec![f64::from(i) / 3., 5.]);
+        }
+        tree.update(1, vec![100., 100.]);
+        assert_eq!(tree.validate(), Ok(()));
+
+        let leaf_index = tree.points[1].parent_index;
+        tree.nodes[leaf_index].rect.high[0] = 50.;
+        assert_eq!(
+            tree.validate(),
+            Err(ValidationError::PointOutsideRect { point_index: 1 })
+        );
+        tree.nodes[leaf_index].rect.high[0] = 100.;
+        tree.points[1].parent_index = usize::MAX;
+        assert_eq!(
+            tree.validate(),
+            Err(ValidationError::PointParentMismatch { point_index: 1 })
+        );
+        tree.points[1].parent_index = leaf_index;
+        assert_eq!(tree.validate(), Ok(()));
+
+        tree.num_points += 1;
+        assert!(matches!(
+            tree.validate(),
+            Err(ValidationError::NumPointsMismatch { .. })
+        ));
+        tree.num_points -= 1;
+
+        let child_index = tree.nodes[tree.root_index].children()[0];
+        tree.nodes[tree.root_index].children_mut()[0] = 9999;
+        assert_eq!(
+            tree.validate(),
+            Err(ValidationError::NodeIndexOutOfRange {
+                node_index: tree.root_index,
+                child_index: 9999
+            })
+        );
+        tree.nodes[tree.root_index].children_mut()[0] = child_index;
+
+        tree.nodes[leaf_index].points_mut()[0] = 9999;
+        assert_eq!(
+            tree.validate(),
+            Err(ValidationError::PointIndexOutOfRange {
+                node_index: leaf_index,
+                point_index: 9999
+            })
+        );
+    }
+}
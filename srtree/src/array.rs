@@ -0,0 +1,178 @@
+use crate::{
+    algorithm::batch::MaybeSync, error::Error, measure::distance::Metric, params::Params, SRTree,
+};
+use ndarray::{Array2, ArrayView1, ArrayView2};
+use ordered_float::Float;
+
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    /// Builds `SRTree` with the rows of a matrix as points.
+    ///
+    /// # Errors
+    /// * `Error::Empty` if the matrix is empty.
+    /// * `Error::NonFiniteCoordinate` if a coordinate is NaN or infinite.
+    pub fn from_array(points: ArrayView2<T>, params: Params, metric: M) -> Result<Self, Error> {
+        if let Some(coords) = points.as_slice() {
+            return SRTree::from_flat(coords, points.ncols(), params, metric);
+        }
+        let coords: Vec<T> = points.iter().copied().collect();
+        SRTree::from_flat(&coords, points.ncols(), params, metric)
+    }
+
+    /// Runs [`SRTree::query`] with the coordinates held in an array.
+    pub fn query_view(&self, point: ArrayView1<T>, k: usize) -> (Vec<usize>, Vec<T>) {
+        match point.as_slice() {
+            Some(coords) => self.query(coords, k),
+            None => self.query(&point.to_vec(), k),
+        }
+    }
+
+    /// Runs [`SRTree::query_radius`] with the coordinates held in an array.
+    pub fn query_radius_view(&self, point: ArrayView1<T>, radius: T) -> Vec<usize> {
+        match point.as_slice() {
+            Some(coords) => self.query_radius(coords, radius),
+            None => self.query_radius(&point.to_vec(), radius),
+        }
+    }
+}
+
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T> + MaybeSync,
+{
+    /// Finds the `k` nearest neighbors of every row of `points`, like [`SRTree::query_batch`].
+    ///
+    /// Row `i` of the returned arrays holds the indices and distances of the neighbors of row
+    /// `i` of `points`, from the closest one. If the tree holds fewer than `k` points, the arrays
+    /// have as many columns as there are points.
+    ///
+    /// # Errors
+    /// * `Error::QueryDimensionMismatch` if `points` does not have a column per dimension of the
+    ///   tree.
+    pub fn query_array(
+        &self,
+        points: ArrayView2<T>,
+        k: usize,
+    ) -> Result<(Array2<usize>, Array2<T>), Error> {
+        self.check_query_columns(points)?;
+        let queries: Vec<Vec<T>> = points.rows().into_iter().map(|row| row.to_vec()).collect();
+        let shape = (queries.len(), k.min(self.num_points()));
+        let mut indices = Array2::zeros(shape);
+        let mut distances = Array2::zeros(shape);
+        for (i, (row_indices, row_distances)) in
+            self.query_batch(&queries, k).into_iter().enumerate()
+        {
+            for (j, (index, distance)) in row_indices.into_iter().zip(row_distances).enumerate() {
+                indices[[i, j]] = index;
+                distances[[i, j]] = distance;
+            }
+        }
+        Ok((indices, distances))
+    }
+
+    /// Finds the points within `radius` of every row of `points`, like
+    /// [`SRTree::query_radius_batch`].
+    ///
+    /// # Errors
+    /// * `Error::QueryDimensionMismatch` if `points` does not have a column per dimension of the
+    ///   tree.
+    pub fn query_radius_array(
+        &self,
+        points: ArrayView2<T>,
+        radius: T,
+    ) -> Result<Vec<Vec<usize>>, Error> {
+        self.check_query_columns(points)?;
+        let queries: Vec<Vec<T>> = points.rows().into_iter().map(|row| row.to_vec()).collect();
+        Ok(self.query_radius_batch(&queries, radius))
+    }
+
+    fn check_query_columns(&self, points: ArrayView2<T>) -> Result<(), Error> {
+        if points.ncols() != self.params.dimension {
+            return Err(Error::QueryDimensionMismatch {
+                expected: self.params.dimension,
+                found: points.ncols(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, Euclidean, Params, SRTree};
+    use ndarray::{arr1, arr2, Array2};
+
+    #[test]
+    pub fn test_from_array() {
+        let points = arr2(&[[0., 0.], [1., 1.], [2., 2.], [3., 3.]]);
+        let tree = SRTree::from_array(
+            points.view(),
+            Params::default_params(),
+            Euclidean::default(),
+        )
+        .expect("Failed to build SRTree");
+        assert_eq!(tree.num_points(), 4);
+        assert_eq!(tree.coords_of(3), [3., 3.]);
+
+        // columns of the transposed matrix are not contiguous
+        let points = arr2(&[[0., 1., 2.], [5., 6., 7.]]);
+        let tree = SRTree::from_array(points.t(), Params::default_params(), Euclidean::default())
+            .expect("Failed to build SRTree");
+        assert_eq!(tree.coords_of(1), [1., 6.]);
+        assert_eq!(tree.query_view(arr1(&[2., 7.]).view(), 1).0, vec![2]);
+        assert_eq!(tree.query_radius_view(points.column(1), 0.), vec![1]);
+
+        let points = Array2::<f64>::zeros((0, 2));
+        let tree = SRTree::from_array(
+            points.view(),
+            Params::default_params(),
+            Euclidean::default(),
+        );
+        assert!(matches!(tree, Err(Error::Empty)));
+    }
+
+    #[test]
+    pub fn test_query_array() {
+        let points: Vec<Vec<f64>> = (0..100).map(|i| vec![f64::from(i), 0.0]).collect();
+        let tree = SRTree::euclidean_with_params(&points, Params::new(2, 5).unwrap())
+            .expect("Failed to build SRTree");
+        let queries = arr2(&[[10.2, 1.], [50., 0.], [-3., 0.]]);
+
+        let (indices, distances) = tree.query_array(queries.view(), 3).unwrap();
+        assert_eq!(indices.shape(), &[3, 3]);
+        for (i, query) in queries.rows().into_iter().enumerate() {
+            let (expected_indices, expected_distances) = tree.query_view(query, 3);
+            assert_eq!(indices.row(i).to_vec(), expected_indices);
+            assert_eq!(distances.row(i).to_vec(), expected_distances);
+        }
+        assert_eq!(indices.row(2).to_vec(), vec![0, 1, 2]);
+
+        let (indices, _) = tree.query_array(queries.view(), 1000).unwrap();
+        assert_eq!(indices.shape(), &[3, 100]);
+
+        let neighbors = tree.query_radius_array(queries.view(), 1.).unwrap();
+        assert_eq!(neighbors.len(), 3);
+        assert_eq!(neighbors[1].len(), 3);
+        assert!(neighbors[2].is_empty());
+
+        let queries = arr2(&[[10.2, 1., 0.]]);
+        assert!(matches!(
+            tree.query_array(queries.view(), 3),
+            Err(Error::QueryDimensionMismatch {
+                expected: 2,
+                found: 3
+            })
+        ));
+        assert!(matches!(
+            tree.query_radius_array(queries.t(), 1.),
+            Err(Error::QueryDimensionMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
+}
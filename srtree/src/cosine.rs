@@ -0,0 +1,205 @@
+use crate::transform::{PointTransform, TransformedSRTree};
+use ordered_float::Float;
+
+/// Cosine distance, one minus the cosine of the angle between two vectors.
+///
+/// Cosine distance breaks the triangle inequality that the sphere and rect bounds rely on, so
+/// it is not a [`Metric`]. As a [`PointTransform`], it normalizes points to unit length;
+/// between unit vectors, the squared Euclidean distance is twice the cosine distance, so both
+/// give the same neighbors.
+///
+/// [`Metric`]: crate::Metric
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cosine {}
+
+impl Cosine {
+    /// Returns NaN if either vector has no direction.
+    #[must_use]
+    pub fn distance<T: Float>(&self, point1: &[T], point2: &[T]) -> T {
+        T::one() - cosine(point1, point2)
+    }
+}
+
+impl<T> PointTransform<T> for Cosine
+where
+    T: Float + Send + Sync,
+{
+    fn transform(&self, coords: &[T]) -> Option<Vec<T>> {
+        normalize(coords)
+    }
+
+    fn distance_from_euclidean(&self, distance: T) -> T {
+        distance * distance / (T::one() + T::one())
+    }
+
+    fn euclidean_radius(&self, radius: T) -> T {
+        (radius.max(T::zero()) * (T::one() + T::one())).sqrt()
+    }
+}
+
+/// Angular distance, the angle in radians between two vectors.
+///
+/// Angular distance satisfies the triangle inequality, but the point of a rect closest to a
+/// query in angle is not the one the rect bound clamps the query to, so it is not a
+/// [`Metric`] either. As a [`PointTransform`], it normalizes points to unit length; between
+/// unit vectors, the angle grows with the Euclidean distance.
+///
+/// [`Metric`]: crate::Metric
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Angular {}
+
+impl Angular {
+    /// Returns NaN if either vector has no direction.
+    #[must_use]
+    pub fn distance<T: Float>(&self, point1: &[T], point2: &[T]) -> T {
+        // the arccosine of the dot product loses precision for small angles
+        let (norm1, norm2) = (norm(point1), norm(point2));
+        let chord = point1
+            .iter()
+            .zip(point2)
+            .fold(T::zero(), |sum, (a, b)| {
+                sum + (*a / norm1 - *b / norm2).powi(2)
+            })
+            .sqrt();
+        let two = T::one() + T::one();
+        (chord / two).min(T::one()).asin() * two
+    }
+}
+
+impl<T> PointTransform<T> for Angular
+where
+    T: Float + Send + Sync,
+{
+    fn transform(&self, coords: &[T]) -> Option<Vec<T>> {
+        normalize(coords)
+    }
+
+    fn distance_from_euclidean(&self, distance: T) -> T {
+        let two = T::one() + T::one();
+        (distance / two).min(T::one()).asin() * two
+    }
+
+    fn euclidean_radius(&self, radius: T) -> T {
+        let two = T::one() + T::one();
+        if radius >= T::from(std::f64::consts::PI).unwrap() {
+            // antipodal unit vectors may lie slightly farther than 2 apart after rounding
+            return T::infinity();
+        }
+        (radius.max(T::zero()) / two).sin() * two
+    }
+}
+
+/// An [`SRTree`](crate::SRTree) searching points by [`Cosine`] distance.
+pub type CosineSRTree<T> = TransformedSRTree<T, Cosine>;
+
+/// An [`SRTree`](crate::SRTree) searching points by [`Angular`] distance.
+pub type AngularSRTree<T> = TransformedSRTree<T, Angular>;
+
+fn cosine<T: Float>(point1: &[T], point2: &[T]) -> T {
+    let dot = point1
+        .iter()
+        .zip(point2)
+        .fold(T::zero(), |sum, (a, b)| sum + *a * *b);
+    dot / (norm(point1) * norm(point2))
+}
+
+fn norm<T: Float>(coords: &[T]) -> T {
+    coords
+        .iter()
+        .fold(T::zero(), |sum, coord| sum + *coord * *coord)
+        .sqrt()
+}
+
+/// Scales a vector to unit length, or returns `None` if it has no finite direction.
+fn normalize<T: Float>(coords: &[T]) -> Option<Vec<T>> {
+    let norm = norm(coords);
+    if norm == T::zero() || !norm.is_finite() {
+        return None;
+    }
+    Some(coords.iter().map(|coord| *coord / norm).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, Params};
+    use std::f64::consts::PI;
+
+    #[test]
+    pub fn test_cosine_query() {
+        let pts = vec![
+            vec![1., 0.],
+            vec![10., 1.],
+            vec![0., 3.],
+            vec![-2., 0.],
+            vec![1., 1.],
+        ];
+        let tree = CosineSRTree::new(&pts, Params::new(2, 5).unwrap(), Cosine::default())
+            .expect("Failed to build CosineSRTree");
+
+        let (indices, distances) = tree.query(&[5., 0.], 5);
+        assert_eq!(indices, vec![0, 1, 4, 2, 3]);
+        let expected = [0., 1. - 10. / 101_f64.sqrt(), 1. - 0.5_f64.sqrt(), 1., 2.];
+        for ((distance, expected), index) in distances.iter().zip(expected).zip(indices) {
+            assert!((distance - expected).abs() < 1e-12);
+            let distance = Cosine::default().distance(&pts[index], &[5., 0.]);
+            assert!((distance - expected).abs() < 1e-12);
+        }
+
+        let mut indices = tree.query_radius(&[1., 0.1], 0.3);
+        indices.sort_unstable();
+        assert_eq!(indices, vec![0, 1, 4]);
+        assert!(tree.query(&[0., 0.], 1).0.is_empty());
+        assert!(tree.query(&[f64::NAN, 0.], 1).0.is_empty());
+
+        let result = CosineSRTree::new(
+            &[vec![1., 0.], vec![0., 0.]],
+            Params::default_params(),
+            Cosine::default(),
+        );
+        assert!(matches!(result, Err(Error::ZeroVector { row: 1 })));
+    }
+
+    #[test]
+    pub fn test_angular_query() {
+        let pts = vec![vec![1., 0.], vec![1., 1.], vec![0., 3.], vec![-2., 0.]];
+        let mut tree = AngularSRTree::new(&pts, Params::new(2, 5).unwrap(), Angular::default())
+            .expect("Failed to build AngularSRTree");
+
+        let (indices, distances) = tree.query(&[2., 0.], 4);
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+        for (distance, expected) in distances.iter().zip([0., PI / 4., PI / 2., PI]) {
+            assert!((distance - expected).abs() < 1e-12);
+        }
+        assert_eq!(Angular::default().distance(&[1., 0.], &[-1., 0.]), PI);
+
+        let mut indices = tree.query_radius(&[1., 0.], 1.6);
+        indices.sort_unstable();
+        assert_eq!(indices, vec![0, 1, 2]);
+        assert_eq!(tree.query_radius(&[1., 0.], PI).len(), 4);
+
+        assert!(tree.update(3, &[0., -1.]).unwrap());
+        assert_eq!(tree.query(&[0., -5.], 1).0, vec![3]);
+
+        assert!(matches!(
+            tree.insert(&[0., 0.]),
+            Err(Error::ZeroVector { row: 4 })
+        ));
+        assert!(matches!(
+            tree.update(3, &[f64::NAN, 1.]),
+            Err(Error::NonFiniteCoordinate { row: 3, column: 0 })
+        ));
+        assert!(matches!(
+            tree.try_query(&[1.], 1),
+            Err(Error::QueryDimensionMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert!(tree.try_query_radius(&[1., 2., 3.], 1.).is_err());
+        assert_eq!(tree.try_query(&[0., -5.], 1).unwrap().0, vec![3]);
+        assert_eq!(tree.num_points(), 4);
+    }
+}
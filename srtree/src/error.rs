@@ -0,0 +1,78 @@
+use crate::index_file::FormatError;
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum Error {
+    /// No point was given.
+    Empty,
+    /// The point at `row` has `found` coordinates instead of `expected`.
+    DimensionMismatch {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The coordinate at `column` of the point at `row` is NaN or infinite.
+    NonFiniteCoordinate { row: usize, column: usize },
+    /// The point at `row` has length zero, so it has no direction to compare.
+    ZeroVector { row: usize },
+    /// The parameters are inconsistent, for the given reason.
+    InvalidParams(&'static str),
+    /// The parameters of a metric are invalid, for the given reason.
+    InvalidMetric(&'static str),
+    /// The query point has `found` coordinates, while the points of the tree have `expected`.
+    QueryDimensionMismatch { expected: usize, found: usize },
+    /// An index file could not be read or written.
+    Io(io::Error),
+    /// An index file is invalid, or was saved from a tree of another type.
+    Format(FormatError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Empty => write!(f, "no points given"),
+            Error::DimensionMismatch {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "point {row} has {found} coordinates, but {expected} were expected"
+            ),
+            Error::NonFiniteCoordinate { row, column } => {
+                write!(f, "coordinate {column} of point {row} is not finite")
+            }
+            Error::ZeroVector { row } => write!(f, "point {row} has length zero"),
+            Error::InvalidParams(reason) => write!(f, "invalid params: {reason}"),
+            Error::InvalidMetric(reason) => write!(f, "invalid metric: {reason}"),
+            Error::QueryDimensionMismatch { expected, found } => write!(
+                f,
+                "query point has {found} coordinates, but the tree has {expected} dimensions"
+            ),
+            Error::Io(e) => write!(f, "I/O error: {e}"),
+            Error::Format(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Format(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<FormatError> for Error {
+    fn from(e: FormatError) -> Self {
+        Error::Format(e)
+    }
+}
@@ -0,0 +1,727 @@
+//! Native binary index file format.
+//!
+//! All values are little-endian. Scalars are stored as `f32` or `f64`, matching the size of the
+//! tree's coordinate type, and every integer is stored as a `u64` unless noted otherwise.
+//!
+//! | Section     | Content                                                                     |
+//! |-------------|-----------------------------------------------------------------------------|
+//! | header      | magic, version (`u32`), scalar size (`u32`), metric ID (`u32`), number of   |
+//! |             | metric parameters (`u32`), metric parameters (`f64` each), params (minimum, |
+//! |             | maximum, reinsertion fraction as `f64`, dimension), number of points, nodes |
+//! |             | and entries, root index                                                     |
+//! | coordinates | `dimension` scalars per row, one row per point                              |
+//! | points      | per point: radius (scalar), parent index, row of its coordinates            |
+//! | nodes       | per node: leaf flag, height, parent index, first entry, number of entries,  |
+//! |             | centroid index, rect low and high, centroid coordinates, sphere radius      |
+//! | entries     | children of internal nodes and points of leaves, referenced by the nodes    |
+//! | checksum    | 64-bit FNV-1a hash of all the preceding bytes                               |
+//!
+//! Every point and node record has a fixed size, so that records can be located directly.
+
+use crate::{
+    error::Error,
+    measure::distance::{Chebyshev, Euclidean, Manhattan, Metric, Minkowski, WeightedEuclidean},
+    node::{Data, Node},
+    params::Params,
+    shape::{
+        point::{Point, PointEntry},
+        rect::Rect,
+        sphere::Sphere,
+    },
+    SRTree,
+};
+use ordered_float::Float;
+use std::{fmt, fs, mem::size_of, path::Path};
+
+pub(crate) const MAGIC: [u8; 8] = *b"SRTREE\0\0";
+pub(crate) const VERSION: u32 = 2;
+pub(crate) const CHECKSUM_SIZE: usize = 8;
+pub(crate) const POINT_INTEGERS: usize = 2;
+pub(crate) const NODE_INTEGERS: usize = 6;
+
+/// A metric that can be stored in an index file and rebuilt when the file is loaded.
+pub trait PersistentMetric<T>: Metric<T> + Sized {
+    /// Identifies the metric in index files. Each metric type needs its own ID.
+    const ID: u32;
+
+    /// Returns the parameters needed to rebuild the metric.
+    fn parameters(&self) -> Vec<f64> {
+        Vec::new()
+    }
+
+    /// Rebuilds the metric for points of the given dimension from its parameters, or returns
+    /// `None` if they are invalid.
+    fn from_parameters(parameters: &[f64], dimension: usize) -> Option<Self>;
+}
+
+impl<T> PersistentMetric<T> for Euclidean
+where
+    T: Float + Send + Sync,
+{
+    const ID: u32 = 1;
+
+    fn from_parameters(parameters: &[f64], _dimension: usize) -> Option<Self> {
+        parameters.is_empty().then(Euclidean::default)
+    }
+}
+
+impl<T> PersistentMetric<T> for Manhattan
+where
+    T: Float + Send + Sync,
+{
+    const ID: u32 = 2;
+
+    fn from_parameters(parameters: &[f64], _dimension: usize) -> Option<Self> {
+        parameters.is_empty().then(Manhattan::default)
+    }
+}
+
+impl<T> PersistentMetric<T> for Chebyshev
+where
+    T: Float + Send + Sync,
+{
+    const ID: u32 = 3;
+
+    fn from_parameters(parameters: &[f64], _dimension: usize) -> Option<Self> {
+        parameters.is_empty().then(Chebyshev::default)
+    }
+}
+
+impl<T> PersistentMetric<T> for Minkowski
+where
+    T: Float + Send + Sync,
+{
+    const ID: u32 = 4;
+
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.p()]
+    }
+
+    fn from_parameters(parameters: &[f64], _dimension: usize) -> Option<Self> {
+        match parameters {
+            [p] => Minkowski::new(*p).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl<T> PersistentMetric<T> for WeightedEuclidean<T>
+where
+    T: Float + Send + Sync,
+{
+    const ID: u32 = 5;
+
+    fn parameters(&self) -> Vec<f64> {
+        self.weights()
+            .iter()
+            .map(|weight| weight.to_f64().unwrap_or(f64::NAN))
+            .collect()
+    }
+
+    fn from_parameters(parameters: &[f64], dimension: usize) -> Option<Self> {
+        if parameters.len() != dimension {
+            return None;
+        }
+        let weights: Option<Vec<T>> = parameters.iter().map(|weight| T::from(*weight)).collect();
+        WeightedEuclidean::new(weights?).ok()
+    }
+}
+
+/// Problems with the content of an index file.
+#[derive(Debug)]
+pub enum FormatError {
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    ChecksumMismatch,
+    ScalarSizeMismatch { expected: usize, found: usize },
+    MetricMismatch { expected: u32, found: u32 },
+    InvalidMetricParameters,
+    Corrupted(&'static str),
+    Unmappable(&'static str),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::InvalidMagic => write!(f, "not an SRTree index file"),
+            FormatError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported index file version {version} (expected {VERSION})"
+            ),
+            FormatError::ChecksumMismatch => write!(f, "index file checksum mismatch"),
+            FormatError::ScalarSizeMismatch { expected, found } => write!(
+                f,
+                "index file stores {found}-byte scalars, but {expected}-byte scalars were expected"
+            ),
+            FormatError::MetricMismatch { expected, found } => write!(
+                f,
+                "index file was built with metric {found}, but metric {expected} was expected"
+            ),
+            FormatError::InvalidMetricParameters => {
+                write!(f, "invalid metric parameters in index file")
+            }
+            FormatError::Corrupted(reason) => write!(f, "corrupted index file: {reason}"),
+            FormatError::Unmappable(reason) => write!(f, "cannot map index file: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+pub(crate) fn checksum(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// Validated header of an index file, with the offsets of its sections.
+pub(crate) struct Header {
+    pub metric_parameters: Vec<f64>,
+    pub params: Params,
+    pub num_points: usize,
+    pub num_nodes: usize,
+    pub num_entries: usize,
+    pub root_index: usize,
+    pub coordinates_offset: usize,
+    pub points_offset: usize,
+    pub nodes_offset: usize,
+    pub entries_offset: usize,
+    pub node_size: usize,
+}
+
+/// Integer fields of a node record, checked against the sizes of the sections.
+pub(crate) struct NodeRecord {
+    pub is_leaf: bool,
+    pub height: usize,
+    pub parent_index: usize,
+    pub first_entry: usize,
+    pub number_of_entries: usize,
+    pub center_index: usize,
+}
+
+impl Header {
+    /// Checks the magic number, version, checksum, scalar type and metric of the file, and
+    /// computes the offsets of its sections.
+    pub(crate) fn parse<T, M>(bytes: &[u8]) -> Result<Header, FormatError>
+    where
+        M: PersistentMetric<T>,
+    {
+        if bytes.len() < MAGIC.len() || bytes[..MAGIC.len()] != MAGIC {
+            return Err(FormatError::InvalidMagic);
+        }
+        let mut reader = Reader::new(bytes, MAGIC.len());
+        let version = reader.read_u32()?;
+        if version != VERSION {
+            return Err(FormatError::UnsupportedVersion(version));
+        }
+        if bytes.len() < CHECKSUM_SIZE {
+            return Err(FormatError::Corrupted("file is truncated"));
+        }
+        let (content, stored) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+        if checksum(content) != u64::from_le_bytes(stored.try_into().unwrap()) {
+            return Err(FormatError::ChecksumMismatch);
+        }
+
+        let scalar_size = reader.read_u32()? as usize;
+        if scalar_size != size_of::<T>() {
+            return Err(FormatError::ScalarSizeMismatch {
+                expected: size_of::<T>(),
+                found: scalar_size,
+            });
+        }
+        let metric_id = reader.read_u32()?;
+        if metric_id != M::ID {
+            return Err(FormatError::MetricMismatch {
+                expected: M::ID,
+                found: metric_id,
+            });
+        }
+        let num_metric_parameters = reader.read_u32()? as usize;
+        let metric_parameters = (0..num_metric_parameters)
+            .map(|_| reader.read_f64())
+            .collect::<Result<Vec<f64>, FormatError>>()?;
+
+        let params = Params {
+            min_number_of_elements: reader.read_usize()?,
+            max_number_of_elements: reader.read_usize()?,
+            reinsertion_fraction: reader.read_f64()?,
+            dimension: reader.read_usize()?,
+        };
+        let num_points = reader.read_usize()?;
+        let num_nodes = reader.read_usize()?;
+        let num_entries = reader.read_usize()?;
+        let root_index = reader.read_usize()?;
+        if root_index >= num_nodes && root_index != usize::MAX {
+            return Err(FormatError::Corrupted("root index out of range"));
+        }
+
+        let coordinates_offset = reader.position;
+        let dimension = params.dimension;
+        let node_size = 8 * NODE_INTEGERS + (3 * dimension + 1) * scalar_size;
+        let sections = [
+            (num_points, dimension * scalar_size),
+            (num_points, scalar_size + 8 * POINT_INTEGERS),
+            (num_nodes, node_size),
+            (num_entries, 8),
+        ];
+        let mut offsets = [coordinates_offset; 5];
+        for (i, (count, record_size)) in sections.iter().enumerate() {
+            let size = count
+                .checked_mul(*record_size)
+                .ok_or(FormatError::Corrupted("section size overflow"))?;
+            offsets[i + 1] = offsets[i]
+                .checked_add(size)
+                .ok_or(FormatError::Corrupted("section size overflow"))?;
+        }
+        if offsets[4] != content.len() {
+            return Err(FormatError::Corrupted("unexpected file size"));
+        }
+
+        Ok(Header {
+            metric_parameters,
+            params,
+            num_points,
+            num_nodes,
+            num_entries,
+            root_index,
+            coordinates_offset,
+            points_offset: offsets[1],
+            nodes_offset: offsets[2],
+            entries_offset: offsets[3],
+            node_size,
+        })
+    }
+
+    pub(crate) fn read_point<T>(
+        &self,
+        bytes: &[u8],
+        point_index: usize,
+    ) -> Result<PointEntry<T>, FormatError>
+    where
+        T: Float,
+    {
+        let point_size = size_of::<T>() + 8 * POINT_INTEGERS;
+        let mut reader = Reader::new(bytes, self.points_offset + point_index * point_size);
+        let point = PointEntry {
+            radius: reader.read_scalar()?,
+            parent_index: reader.read_usize()?,
+            row: reader.read_usize()?,
+        };
+        if point.parent_index >= self.num_nodes && point.parent_index != usize::MAX {
+            return Err(FormatError::Corrupted("parent index out of range"));
+        }
+        if point.row >= self.num_points {
+            return Err(FormatError::Corrupted("row out of range"));
+        }
+        Ok(point)
+    }
+
+    pub(crate) fn read_node(
+        &self,
+        bytes: &[u8],
+        node_index: usize,
+    ) -> Result<NodeRecord, FormatError> {
+        let mut reader = Reader::new(bytes, self.nodes_offset + node_index * self.node_size);
+        let record = NodeRecord {
+            is_leaf: reader.read_usize()? == 1,
+            height: reader.read_usize()?,
+            parent_index: reader.read_usize()?,
+            first_entry: reader.read_usize()?,
+            number_of_entries: reader.read_usize()?,
+            center_index: reader.read_usize()?,
+        };
+        if record.parent_index >= self.num_nodes && record.parent_index != usize::MAX {
+            return Err(FormatError::Corrupted("parent index out of range"));
+        }
+        let last_entry = record.first_entry.checked_add(record.number_of_entries);
+        if last_entry.map_or(true, |last_entry| last_entry > self.num_entries) {
+            return Err(FormatError::Corrupted("entry range out of bounds"));
+        }
+        Ok(record)
+    }
+
+    /// Reads the children of an internal node or the points of a leaf.
+    pub(crate) fn read_entries(
+        &self,
+        bytes: &[u8],
+        record: &NodeRecord,
+    ) -> Result<Vec<usize>, FormatError> {
+        let limit = if record.is_leaf {
+            self.num_points
+        } else {
+            self.num_nodes
+        };
+        let mut reader = Reader::new(bytes, self.entries_offset + record.first_entry * 8);
+        (0..record.number_of_entries)
+            .map(|_| match reader.read_usize()? {
+                entry if entry < limit => Ok(entry),
+                _ => Err(FormatError::Corrupted("entry out of range")),
+            })
+            .collect()
+    }
+}
+
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    pub position: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8], position: usize) -> Reader<'a> {
+        Reader { bytes, position }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], FormatError> {
+        let end = self.position + N;
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or(FormatError::Corrupted("file is truncated"))?;
+        self.position = end;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    pub(crate) fn read_u32(&mut self) -> Result<u32, FormatError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn read_usize(&mut self) -> Result<usize, FormatError> {
+        let value = u64::from_le_bytes(self.take()?);
+        if value == u64::MAX {
+            return Ok(usize::MAX);
+        }
+        usize::try_from(value).map_err(|_| FormatError::Corrupted("integer out of range"))
+    }
+
+    pub(crate) fn read_f64(&mut self) -> Result<f64, FormatError> {
+        Ok(f64::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn read_scalar<T>(&mut self) -> Result<T, FormatError>
+    where
+        T: Float,
+    {
+        let value = if size_of::<T>() == 4 {
+            T::from(f32::from_le_bytes(self.take()?))
+        } else {
+            T::from(f64::from_le_bytes(self.take()?))
+        };
+        value.ok_or(FormatError::Corrupted("invalid scalar"))
+    }
+
+    pub(crate) fn read_scalars<T>(&mut self, count: usize) -> Result<Vec<T>, FormatError>
+    where
+        T: Float,
+    {
+        (0..count).map(|_| self.read_scalar()).collect()
+    }
+}
+
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn write_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        let value = if value == usize::MAX {
+            u64::MAX
+        } else {
+            value as u64
+        };
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_f64(&mut self, value: f64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_scalar<T>(&mut self, value: T)
+    where
+        T: Float,
+    {
+        if size_of::<T>() == 4 {
+            self.write_f32(value.to_f32().unwrap());
+        } else {
+            self.write_f64(value.to_f64().unwrap());
+        }
+    }
+
+    fn write_f32(&mut self, value: f32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_scalars<T>(&mut self, values: &[T])
+    where
+        T: Float,
+    {
+        for value in values {
+            self.write_scalar(*value);
+        }
+    }
+}
+
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: PersistentMetric<T>,
+{
+    /// Saves the tree to an index file, which can be loaded back with [`SRTree::load`].
+    ///
+    /// # Errors
+    /// * `Error::Io` if the file cannot be written.
+    ///
+    /// # Panics
+    /// * If the coordinate type is neither 4 nor 8 bytes long.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        assert!(
+            size_of::<T>() == 4 || size_of::<T>() == 8,
+            "only 4-byte and 8-byte scalars can be saved"
+        );
+        let dimension = self.params.dimension;
+        let mut writer = Writer { bytes: Vec::new() };
+        writer.bytes.extend_from_slice(&MAGIC);
+        writer.write_u32(VERSION);
+        writer.write_u32(u32::try_from(size_of::<T>()).unwrap());
+        writer.write_u32(M::ID);
+        let metric_parameters = self.metric.parameters();
+        writer.write_u32(u32::try_from(metric_parameters.len()).unwrap());
+        for parameter in metric_parameters {
+            writer.write_f64(parameter);
+        }
+        writer.write_usize(self.params.min_number_of_elements);
+        writer.write_usize(self.params.max_number_of_elements);
+        writer.write_f64(self.params.reinsertion_fraction);
+        writer.write_usize(dimension);
+
+        let num_entries = self.nodes.iter().map(Node::immed_children).sum();
+        writer.write_usize(self.points.len());
+        writer.write_usize(self.nodes.len());
+        writer.write_usize(num_entries);
+        writer.write_usize(self.root_index);
+
+        writer.write_scalars(&self.coords);
+        for point in &self.points {
+            writer.write_scalar(point.radius);
+            writer.write_usize(point.parent_index);
+            writer.write_usize(point.row);
+        }
+
+        let mut entries = Vec::with_capacity(num_entries);
+        for node in &self.nodes {
+            let node_entries = if node.is_leaf() {
+                node.points()
+            } else {
+                node.children()
+            };
+            writer.write_usize(usize::from(node.is_leaf()));
+            writer.write_usize(node.height);
+            writer.write_usize(node.parent_index);
+            writer.write_usize(entries.len());
+            writer.write_usize(node_entries.len());
+            writer.write_usize(node.sphere.center.index);
+            writer.write_scalars(&node.rect.low);
+            writer.write_scalars(&node.rect.high);
+            writer.write_scalars(&node.sphere.center.coords);
+            writer.write_scalar(node.sphere.radius);
+            entries.extend_from_slice(node_entries);
+        }
+        for entry in entries {
+            writer.write_usize(entry);
+        }
+
+        let checksum = checksum(&writer.bytes);
+        writer.bytes.extend_from_slice(&checksum.to_le_bytes());
+        fs::write(path, writer.bytes)?;
+        Ok(())
+    }
+
+    /// Loads a tree saved with [`SRTree::save`].
+    ///
+    /// # Errors
+    /// * `Error::Io` if the file cannot be read.
+    /// * `Error::Format` if the file is not a valid index file or was saved from a tree of another
+    ///   type; see [`FormatError`] for the possible causes.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let bytes = fs::read(path)?;
+        let header = Header::parse::<T, M>(&bytes)?;
+        let dimension = header.params.dimension;
+        let metric = M::from_parameters(&header.metric_parameters, dimension)
+            .ok_or(FormatError::InvalidMetricParameters)?;
+
+        let mut reader = Reader::new(&bytes, header.coordinates_offset);
+        let coords = reader.read_scalars(header.num_points * dimension)?;
+        let points = (0..header.num_points)
+            .map(|point_index| header.read_point(&bytes, point_index))
+            .collect::<Result<Vec<PointEntry<T>>, FormatError>>()?;
+
+        let mut nodes = Vec::with_capacity(header.num_nodes);
+        for node_index in 0..header.num_nodes {
+            let record = header.read_node(&bytes, node_index)?;
+            let entries = header.read_entries(&bytes, &record)?;
+            let mut reader = Reader::new(
+                &bytes,
+                header.nodes_offset + node_index * header.node_size + 8 * NODE_INTEGERS,
+            );
+            let low = reader.read_scalars(dimension)?;
+            let high = reader.read_scalars(dimension)?;
+            let center = reader.read_scalars(dimension)?;
+            let radius = reader.read_scalar()?;
+
+            let data = if record.is_leaf {
+                Data::Points(entries)
+            } else {
+                Data::Nodes(entries)
+            };
+            let mut node = Node::new(
+                Rect::new(low, high),
+                Sphere::new(Point::new(center, record.center_index), radius),
+                data,
+                record.height,
+            );
+            node.parent_index = record.parent_index;
+            nodes.push(node);
+        }
+
+        let num_points = points
+            .iter()
+            .filter(|point| point.parent_index != usize::MAX)
+            .count();
+        Ok(SRTree {
+            root_index: header.root_index,
+            coords,
+            points,
+            nodes,
+            params: header.params,
+            metric,
+            num_points,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        env::temp_dir().join(format!("srtree-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    pub fn test_save_and_load() {
+        let points: Vec<Vec<f32>> = (0..100u8)
+            .map(|i| vec![f32::from(i), f32::from(i % 10)])
+            .collect();
+        let mut tree = SRTree::euclidean_with_params(&points, Params::new(2, 5).unwrap()).unwrap();
+        tree.remove(3);
+        let path = temp_path("save-and-load");
+        tree.save(&path).unwrap();
+
+        let loaded = SRTree::<f32, Euclidean>::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.num_points(), 99);
+        assert_eq!(loaded.num_nodes(), tree.num_nodes());
+        assert_eq!(loaded.query(&[3., 3.], 5), tree.query(&[3., 3.], 5));
+        assert_eq!(
+            loaded.query_radius(&[3., 3.], 2.),
+            tree.query_radius(&[3., 3.], 2.)
+        );
+    }
+
+    #[test]
+    pub fn test_save_and_load_metric_parameters() {
+        let points: Vec<Vec<f64>> = (0..50).map(|i| vec![f64::from(i), 0.]).collect();
+        let tree = SRTree::new(
+            &points,
+            Params::default_params(),
+            Minkowski::new(3.).unwrap(),
+        )
+        .unwrap();
+        let path = temp_path("metric-parameters");
+        tree.save(&path).unwrap();
+
+        let loaded = SRTree::<f64, Minkowski>::load(&path).unwrap();
+        assert_eq!(loaded.metric.p(), 3.);
+        assert_eq!(loaded.query(&[7., 1.], 3), tree.query(&[7., 1.], 3));
+        let result = SRTree::<f64, Manhattan>::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            result,
+            Err(Error::Format(FormatError::MetricMismatch {
+                expected: 2,
+                found: 4
+            }))
+        ));
+
+        let tree = SRTree::new(
+            &points,
+            Params::default_params(),
+            WeightedEuclidean::new(vec![1., 4.]).unwrap(),
+        )
+        .unwrap();
+        tree.save(&path).unwrap();
+        let loaded = SRTree::<f64, WeightedEuclidean<f64>>::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.metric.weights(), [1., 4.]);
+        assert!(WeightedEuclidean::<f64>::from_parameters(&[1., 4.], 3).is_none());
+    }
+
+    #[test]
+    pub fn test_load_invalid_file() {
+        let points = vec![vec![0., 0.], vec![1., 1.]];
+        let tree = SRTree::euclidean(&points).unwrap();
+        let path = temp_path("invalid-file");
+        tree.save(&path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+
+        let mut damaged = bytes.clone();
+        let last_coordinate = damaged.len() - 100;
+        damaged[last_coordinate] ^= 1;
+        fs::write(&path, &damaged).unwrap();
+        let result = SRTree::<f64, Euclidean>::load(&path);
+        assert!(matches!(
+            result,
+            Err(Error::Format(FormatError::ChecksumMismatch))
+        ));
+
+        let mut old_version = bytes.clone();
+        old_version[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&0u32.to_le_bytes());
+        fs::write(&path, &old_version).unwrap();
+        let result = SRTree::<f64, Euclidean>::load(&path);
+        assert!(matches!(
+            result,
+            Err(Error::Format(FormatError::UnsupportedVersion(0)))
+        ));
+
+        fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+        assert!(SRTree::<f64, Euclidean>::load(&path).is_err());
+
+        fs::write(&path, &bytes).unwrap();
+        let result = SRTree::<f32, Euclidean>::load(&path);
+        assert!(matches!(
+            result,
+            Err(Error::Format(FormatError::ScalarSizeMismatch {
+                expected: 4,
+                found: 8
+            }))
+        ));
+
+        fs::write(&path, b"not an index").unwrap();
+        let result = SRTree::<f64, Euclidean>::load(&path);
+        assert!(matches!(
+            result,
+            Err(Error::Format(FormatError::InvalidMagic))
+        ));
+
+        fs::remove_file(&path).unwrap();
+        let result = SRTree::<f64, Euclidean>::load(&path);
+        assert!(matches!(result, Err(Error::Io(_))));
+    }
+}
@@ -1,12 +1,32 @@
 mod algorithm;
+#[cfg(feature = "ndarray")]
+mod array;
+mod cosine;
+mod error;
+mod index_file;
+mod mahalanobis;
+mod map;
 mod measure;
+#[cfg(feature = "mmap")]
+mod mmap;
 mod node;
 mod params;
 mod shape;
 mod srtree;
-#[allow(dead_code)]
 mod stats;
-pub use crate::measure::distance::Euclidean;
+mod transform;
+pub use crate::algorithm::nearest::NearestIter;
+pub use crate::algorithm::validation::ValidationError;
+pub use crate::cosine::{Angular, AngularSRTree, Cosine, CosineSRTree};
+pub use crate::error::Error;
+pub use crate::index_file::{FormatError, PersistentMetric};
+pub use crate::mahalanobis::{Mahalanobis, MahalanobisSRTree};
+pub use crate::map::SRTreeMap;
 pub use crate::measure::distance::Metric;
+pub use crate::measure::distance::{Chebyshev, Euclidean, Manhattan, Minkowski, WeightedEuclidean};
+#[cfg(feature = "mmap")]
+pub use crate::mmap::MmapSRTree;
 pub use crate::params::Params;
 pub use crate::srtree::SRTree;
+pub use crate::stats::{LevelReport, QueryStats, TreeReport};
+pub use crate::transform::{PointTransform, TransformedSRTree};
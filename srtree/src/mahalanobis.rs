@@ -0,0 +1,243 @@
+use crate::{
+    error::Error,
+    transform::{PointTransform, TransformedSRTree},
+};
+use ordered_float::Float;
+
+/// Mahalanobis distance, the Euclidean distance between points transformed by a whitening
+/// matrix `W`, where `Wᵀ W` is the inverse of the covariance matrix.
+///
+/// Rect bounds do not hold for distances that mix dimensions, so this is not a [`Metric`]
+/// for [`SRTree`]; [`MahalanobisSRTree`] whitens the points instead.
+///
+/// [`Metric`]: crate::Metric
+/// [`SRTree`]: crate::SRTree
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "MahalanobisFields<T>",
+        bound(deserialize = "T: Float + serde::Deserialize<'de>")
+    )
+)]
+pub struct Mahalanobis<T> {
+    /// Whitening matrix, row by row.
+    whitening: Vec<T>,
+    dimension: usize,
+}
+
+impl<T> Mahalanobis<T>
+where
+    T: Float,
+{
+    /// Creates the distance for the given covariance matrix, of which only the lower triangle
+    /// is read.
+    ///
+    /// # Errors
+    /// * `Error::InvalidMetric` if the matrix is not square or not positive definite.
+    pub fn from_covariance(covariance: &[Vec<T>]) -> Result<Mahalanobis<T>, Error> {
+        let dimension = square_dimension(covariance)?;
+        // Cholesky decomposition into `L Lᵀ`, whose inverse is `L⁻ᵀ L⁻¹`, so `W = L⁻¹`
+        let mut lower = vec![T::zero(); dimension * dimension];
+        for j in 0..dimension {
+            let mut diagonal = covariance[j][j];
+            for k in 0..j {
+                diagonal = diagonal - lower[j * dimension + k].powi(2);
+            }
+            if diagonal.is_nan() || diagonal <= T::zero() {
+                return Err(Error::InvalidMetric(
+                    "covariance matrix must be positive definite",
+                ));
+            }
+            lower[j * dimension + j] = diagonal.sqrt();
+            for i in j + 1..dimension {
+                let mut sum = covariance[i][j];
+                for k in 0..j {
+                    sum = sum - lower[i * dimension + k] * lower[j * dimension + k];
+                }
+                lower[i * dimension + j] = sum / lower[j * dimension + j];
+            }
+        }
+
+        let mut whitening = vec![T::zero(); dimension * dimension];
+        for j in 0..dimension {
+            whitening[j * dimension + j] = lower[j * dimension + j].recip();
+            for i in j + 1..dimension {
+                let mut sum = T::zero();
+                for k in j..i {
+                    sum = sum + lower[i * dimension + k] * whitening[k * dimension + j];
+                }
+                whitening[i * dimension + j] = -sum / lower[i * dimension + i];
+            }
+        }
+        Ok(Mahalanobis {
+            whitening,
+            dimension,
+        })
+    }
+
+    /// Creates the distance for the given whitening matrix.
+    ///
+    /// # Errors
+    /// * `Error::InvalidMetric` if the matrix is not square.
+    pub fn from_whitening(whitening: &[Vec<T>]) -> Result<Mahalanobis<T>, Error> {
+        let dimension = square_dimension(whitening)?;
+        Ok(Mahalanobis {
+            whitening: whitening.iter().flatten().copied().collect(),
+            dimension,
+        })
+    }
+
+    /// Transforms a point into the space where this distance is Euclidean.
+    ///
+    /// # Panics
+    /// * If the dimension of `coords` differs from the dimension of the matrix.
+    #[must_use]
+    pub fn whiten(&self, coords: &[T]) -> Vec<T> {
+        assert_eq!(coords.len(), self.dimension, "dimension mismatch");
+        self.whitening
+            .chunks(self.dimension)
+            .map(|row| {
+                row.iter()
+                    .zip(coords)
+                    .fold(T::zero(), |sum, (w, coord)| sum + *w * *coord)
+            })
+            .collect()
+    }
+
+    /// # Panics
+    /// * If the dimension of a point differs from the dimension of the matrix.
+    #[must_use]
+    pub fn distance(&self, point1: &[T], point2: &[T]) -> T {
+        let difference: Vec<T> = point1.iter().zip(point2).map(|(a, b)| *a - *b).collect();
+        self.whiten(&difference)
+            .into_iter()
+            .fold(T::zero(), |sum, coord| sum + coord * coord)
+            .sqrt()
+    }
+}
+
+/// Deserialized form of [`Mahalanobis`], checked like [`Mahalanobis::from_whitening`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct MahalanobisFields<T> {
+    whitening: Vec<T>,
+    dimension: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Float> TryFrom<MahalanobisFields<T>> for Mahalanobis<T> {
+    type Error = Error;
+
+    fn try_from(fields: MahalanobisFields<T>) -> Result<Self, Error> {
+        let rows: Vec<Vec<T>> = fields
+            .whitening
+            .chunks(fields.dimension.max(1))
+            .map(<[T]>::to_vec)
+            .collect();
+        if rows.len() != fields.dimension {
+            return Err(Error::InvalidMetric("matrix must be square"));
+        }
+        Mahalanobis::from_whitening(&rows)
+    }
+}
+
+/// Returns the dimension of a square matrix with finite entries.
+fn square_dimension<T: Float>(matrix: &[Vec<T>]) -> Result<usize, Error> {
+    let dimension = matrix.len();
+    if dimension == 0 || matrix.iter().any(|row| row.len() != dimension) {
+        return Err(Error::InvalidMetric("matrix must be square"));
+    }
+    if matrix.iter().flatten().any(|entry| !entry.is_finite()) {
+        return Err(Error::InvalidMetric("matrix entries must be finite"));
+    }
+    Ok(dimension)
+}
+
+impl<T> PointTransform<T> for Mahalanobis<T>
+where
+    T: Float + Send + Sync,
+{
+    fn dimension(&self) -> Option<usize> {
+        Some(self.dimension)
+    }
+
+    fn transform(&self, coords: &[T]) -> Option<Vec<T>> {
+        Some(self.whiten(coords))
+    }
+
+    fn distance_from_euclidean(&self, distance: T) -> T {
+        distance
+    }
+
+    fn euclidean_radius(&self, radius: T) -> T {
+        radius
+    }
+}
+
+/// An [`SRTree`](crate::SRTree) searching points by [`Mahalanobis`] distance.
+///
+/// Points are whitened when they are added and queries when they are run, so that the tree
+/// searches by Euclidean distance, which equals the Mahalanobis distance of the original
+/// points.
+pub type MahalanobisSRTree<T> = TransformedSRTree<T, Mahalanobis<T>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Params;
+
+    #[test]
+    pub fn test_from_covariance() {
+        let covariance = vec![vec![4., 2.], vec![2., 5.]];
+        let metric = Mahalanobis::from_covariance(&covariance).unwrap();
+        // the inverse of the covariance matrix is [[5, -2], [-2, 4]] / 16
+        let distance = metric.distance(&[1., 2.], &[0., 0.]);
+        assert!((distance - (13_f64 / 16.).sqrt()).abs() < 1e-12);
+
+        let whitening = vec![vec![0.5, 0.], vec![0., 2.]];
+        let metric = Mahalanobis::from_whitening(&whitening).unwrap();
+        assert_eq!(metric.distance(&[4., 1.], &[0., 0.]), 8_f64.sqrt());
+
+        assert!(Mahalanobis::from_covariance(&[vec![1., 2.], vec![2., 1.]]).is_err());
+        assert!(Mahalanobis::<f64>::from_covariance(&[vec![1., 0.]]).is_err());
+        assert!(Mahalanobis::<f64>::from_whitening(&[]).is_err());
+    }
+
+    #[test]
+    pub fn test_mahalanobis_query() {
+        let pts: Vec<Vec<f64>> = (0..100)
+            .map(|i| vec![f64::from(i % 10), f64::from(i / 10) * 10.])
+            .collect();
+        let metric = Mahalanobis::from_covariance(&[vec![1., 0.], vec![0., 100.]]).unwrap();
+        let tree = MahalanobisSRTree::new(&pts, Params::new(2, 5).unwrap(), metric)
+            .expect("Failed to build MahalanobisSRTree");
+
+        // one step along either axis is at distance 1
+        let mut indices = tree.query_radius(&[5., 50.], 1.);
+        indices.sort_unstable();
+        assert_eq!(indices, vec![45, 54, 55, 56, 65]);
+        let (indices, distances) = tree.query(&[0., 0.], 1);
+        assert_eq!((indices, distances), (vec![0], vec![0.]));
+
+        let mut tree = tree;
+        let index = tree.insert(&[3.5, 35.]).unwrap();
+        assert_eq!(tree.query(&[3.5, 35.5], 1).0, vec![index]);
+        assert!(tree.update(index, &[100., 0.]).unwrap());
+        assert!(tree.insert(&[1.]).is_err());
+        assert_eq!(tree.query(&[99.5, 0.], 1).0, vec![index]);
+        assert!(tree.query(&[f64::NAN, 0.], 1).0.is_empty());
+
+        let metric = tree.transform().clone();
+        let result = MahalanobisSRTree::new(&[vec![1.]], Params::default_params(), metric);
+        assert!(matches!(
+            result,
+            Err(Error::DimensionMismatch {
+                row: 0,
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
+}
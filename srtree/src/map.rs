@@ -0,0 +1,143 @@
+use crate::{error::Error, measure::distance::Metric, params::Params, SRTree};
+use ordered_float::Float;
+
+/// An [`SRTree`] whose points carry values, such as external IDs, returned by queries in place
+/// of point indices.
+///
+/// Values are stored by point index, so they stay attached to their points when other points
+/// are inserted or removed.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        bound(deserialize = "T: Float + Send + Sync + serde::Deserialize<'de>, \
+                       M: Metric<T> + serde::Deserialize<'de>, V: serde::Deserialize<'de>")
+    )
+)]
+pub struct SRTreeMap<T, M, V> {
+    tree: SRTree<T, M>,
+    values: Vec<Option<V>>,
+}
+
+impl<T, M, V> SRTreeMap<T, M, V>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    /// Builds `SRTreeMap` with `(coordinates, value)` pairs, reading them one at a time.
+    ///
+    /// # Errors
+    /// * `Error::Empty` if there is no pair.
+    /// * `Error::DimensionMismatch` if a point does not have the same dimension as the first
+    ///   one.
+    /// * `Error::NonFiniteCoordinate` if a point has a NaN or infinite coordinate.
+    pub fn from_iter<I, P>(entries: I, params: Params, metric: M) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (P, V)>,
+        P: AsRef<[T]>,
+    {
+        let mut values = Vec::new();
+        let pts = entries.into_iter().map(|(point, value)| {
+            values.push(Some(value));
+            point
+        });
+        let tree = SRTree::from_iter(pts, params, metric)?;
+        Ok(SRTreeMap { tree, values })
+    }
+
+    /// Returns the underlying tree, whose point indices are the ones used by this map.
+    pub fn tree(&self) -> &SRTree<T, M> {
+        &self.tree
+    }
+
+    pub fn num_points(&self) -> usize {
+        self.tree.num_points()
+    }
+
+    /// Returns the value of the point with the given index, if it is in the tree.
+    pub fn get(&self, point_index: usize) -> Option<&V> {
+        self.values.get(point_index)?.as_ref()
+    }
+
+    /// Inserts a point with its value and returns the index of the point.
+    ///
+    /// # Panics
+    /// * If the dimension of `coords` differs from the dimension of the tree, or if `coords` has
+    ///   a NaN or infinite coordinate.
+    pub fn insert(&mut self, coords: &[T], value: V) -> usize {
+        let point_index = self.tree.insert(coords.to_vec());
+        self.values.push(Some(value));
+        point_index
+    }
+
+    /// Removes the point with the given index and returns its value, or `None` if there is no
+    /// such point.
+    pub fn remove(&mut self, point_index: usize) -> Option<V> {
+        if !self.tree.remove(point_index) {
+            return None;
+        }
+        self.values[point_index].take()
+    }
+
+    /// Moves the point with the given index to new coordinates, keeping its value.
+    ///
+    /// Returns `false` if there is no such point in the tree.
+    ///
+    /// # Panics
+    /// * If the dimension of `new_coords` differs from the dimension of the tree, or if
+    ///   `new_coords` has a NaN or infinite coordinate.
+    pub fn update(&mut self, point_index: usize, new_coords: &[T]) -> bool {
+        self.tree.update(point_index, new_coords.to_vec())
+    }
+
+    /// Finds the `k` nearest neighbors and returns their values with their distances.
+    pub fn query(&self, point_coords: &[T], k: usize) -> (Vec<&V>, Vec<T>) {
+        let (indices, distances) = self.tree.query(point_coords, k);
+        (self.values_of(&indices), distances)
+    }
+
+    /// Returns the values of the points within `radius`.
+    pub fn query_radius(&self, point_coords: &[T], radius: T) -> Vec<&V> {
+        self.values_of(&self.tree.query_radius(point_coords, radius))
+    }
+
+    fn values_of(&self, point_indices: &[usize]) -> Vec<&V> {
+        point_indices
+            .iter()
+            .filter_map(|point_index| self.get(*point_index))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Euclidean;
+
+    #[test]
+    pub fn test_values() {
+        let entries = (0..50_u32).map(|key| ([f64::from(key), 0.], u64::from(key) + 1000));
+        let mut map =
+            SRTreeMap::from_iter(entries, Params::new(2, 5).unwrap(), Euclidean::default())
+                .expect("Failed to build SRTreeMap");
+        assert_eq!(map.num_points(), 50);
+        assert_eq!(map.get(3), Some(&1003));
+
+        let (values, distances) = map.query(&[10.2, 0.], 2);
+        assert_eq!(values, vec![&1010, &1011]);
+        assert_eq!(distances.len(), 2);
+
+        assert_eq!(map.remove(10), Some(1010));
+        assert_eq!(map.remove(10), None);
+        assert_eq!(map.get(10), None);
+        let index = map.insert(&[10.1, 0.], 7);
+        assert_eq!(index, 50);
+        assert!(map.update(11, &[-5., 0.]));
+
+        let (values, _) = map.query(&[10.2, 0.], 2);
+        assert_eq!(values, vec![&7, &1009]);
+        let mut values = map.query_radius(&[-4., 0.], 1.5);
+        values.sort_unstable();
+        assert_eq!(values, vec![&1011]);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    error::Error,
     node::Node,
     shape::{point::Point, rect::Rect, sphere::Sphere},
     SRTree,
@@ -8,9 +9,16 @@ use ordered_float::Float;
 pub trait Metric<T> {
     fn distance(&self, point1: &[T], point2: &[T]) -> T;
     fn distance_squared(&self, point1: &[T], point2: &[T]) -> T;
+
+    /// Returns the only dimension the metric is defined for, or `None` if it is defined for
+    /// any. Trees reject points of another dimension.
+    fn dimension(&self) -> Option<usize> {
+        None
+    }
 }
 
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Euclidean {}
 
 impl<T> Metric<T> for Euclidean
@@ -33,6 +41,206 @@ where
     }
 }
 
+/// Euclidean distance with each squared difference of coordinates scaled by the weight of its
+/// dimension, to compare features of different scales.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "WeightedEuclideanFields<T>",
+        bound(deserialize = "T: Float + serde::Deserialize<'de>")
+    )
+)]
+pub struct WeightedEuclidean<T> {
+    weights: Vec<T>,
+}
+
+impl<T> WeightedEuclidean<T>
+where
+    T: Float,
+{
+    /// # Errors
+    /// * `Error::InvalidMetric` if a weight is negative, NaN or infinite.
+    pub fn new(weights: Vec<T>) -> Result<WeightedEuclidean<T>, Error> {
+        if weights
+            .iter()
+            .any(|weight| !weight.is_finite() || *weight < T::zero())
+        {
+            return Err(Error::InvalidMetric(
+                "weights must be finite and non-negative",
+            ));
+        }
+        Ok(WeightedEuclidean { weights })
+    }
+
+    /// Returns the weight of each dimension.
+    #[must_use]
+    pub fn weights(&self) -> &[T] {
+        &self.weights
+    }
+}
+
+/// Deserialized form of [`WeightedEuclidean`], checked by [`WeightedEuclidean::new`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct WeightedEuclideanFields<T> {
+    weights: Vec<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Float> TryFrom<WeightedEuclideanFields<T>> for WeightedEuclidean<T> {
+    type Error = Error;
+
+    fn try_from(fields: WeightedEuclideanFields<T>) -> Result<Self, Error> {
+        WeightedEuclidean::new(fields.weights)
+    }
+}
+
+impl<T> Metric<T> for WeightedEuclidean<T>
+where
+    T: Float + Send + Sync,
+{
+    fn distance(&self, point1: &[T], point2: &[T]) -> T {
+        self.distance_squared(point1, point2).sqrt()
+    }
+
+    fn distance_squared(&self, point1: &[T], point2: &[T]) -> T {
+        if point1.len() != point2.len() || point1.len() != self.weights.len() {
+            return T::infinity();
+        }
+        let mut distance = T::zero();
+        for i in 0..point1.len() {
+            distance = distance + self.weights[i] * (point1[i] - point2[i]).powi(2);
+        }
+        distance
+    }
+
+    fn dimension(&self) -> Option<usize> {
+        Some(self.weights.len())
+    }
+}
+
+/// The sum of the absolute differences of the coordinates.
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Manhattan {}
+
+impl<T> Metric<T> for Manhattan
+where
+    T: Float + Send + Sync,
+{
+    fn distance(&self, point1: &[T], point2: &[T]) -> T {
+        if point1.len() != point2.len() {
+            return T::infinity();
+        }
+        let mut distance = T::zero();
+        for i in 0..point1.len() {
+            distance = distance + (point1[i] - point2[i]).abs();
+        }
+        distance
+    }
+
+    fn distance_squared(&self, point1: &[T], point2: &[T]) -> T {
+        self.distance(point1, point2).powi(2)
+    }
+}
+
+/// The largest absolute difference of the coordinates.
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chebyshev {}
+
+impl<T> Metric<T> for Chebyshev
+where
+    T: Float + Send + Sync,
+{
+    fn distance(&self, point1: &[T], point2: &[T]) -> T {
+        if point1.len() != point2.len() {
+            return T::infinity();
+        }
+        let mut distance = T::zero();
+        for i in 0..point1.len() {
+            distance = distance.max((point1[i] - point2[i]).abs());
+        }
+        distance
+    }
+
+    fn distance_squared(&self, point1: &[T], point2: &[T]) -> T {
+        self.distance(point1, point2).powi(2)
+    }
+}
+
+/// The `p`-th root of the sum of the `p`-th powers of the absolute differences of the
+/// coordinates.
+///
+/// `p` must be at least 1 for the triangle inequality, which the search relies on, to hold.
+/// [`Manhattan`], [`Euclidean`] and [`Chebyshev`] are faster for `p` of 1, 2 and infinity.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "MinkowskiFields"))]
+pub struct Minkowski {
+    p: f64,
+}
+
+impl Minkowski {
+    /// # Errors
+    /// * `Error::InvalidMetric` if `p` is less than 1 or NaN.
+    pub fn new(p: f64) -> Result<Minkowski, Error> {
+        if p.is_nan() || p < 1. {
+            return Err(Error::InvalidMetric(
+                "Minkowski exponent must be at least 1",
+            ));
+        }
+        Ok(Minkowski { p })
+    }
+
+    #[must_use]
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+}
+
+/// Deserialized form of [`Minkowski`], checked by [`Minkowski::new`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct MinkowskiFields {
+    p: f64,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<MinkowskiFields> for Minkowski {
+    type Error = Error;
+
+    fn try_from(fields: MinkowskiFields) -> Result<Self, Error> {
+        Minkowski::new(fields.p)
+    }
+}
+
+impl<T> Metric<T> for Minkowski
+where
+    T: Float + Send + Sync,
+{
+    fn distance(&self, point1: &[T], point2: &[T]) -> T {
+        if self.p.is_infinite() {
+            return Chebyshev::default().distance(point1, point2);
+        }
+        if point1.len() != point2.len() {
+            return T::infinity();
+        }
+        let p = T::from(self.p).unwrap();
+        let mut sum = T::zero();
+        for i in 0..point1.len() {
+            sum = sum + (point1[i] - point2[i]).abs().powf(p);
+        }
+        sum.powf(p.recip())
+    }
+
+    fn distance_squared(&self, point1: &[T], point2: &[T]) -> T {
+        self.distance(point1, point2).powi(2)
+    }
+}
+
 impl<T, M> SRTree<T, M>
 where
     T: Float + Send + Sync,
@@ -82,6 +290,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Params;
 
     #[test]
     pub fn test_dimension_mismatch() {
@@ -100,4 +309,39 @@ mod tests {
         let euclidean = Euclidean::default();
         assert_eq!(euclidean.distance(&point1, &point2), 1.);
     }
+
+    #[test]
+    pub fn test_manhattan_chebyshev_minkowski() {
+        let point1 = vec![1., 5., -2.];
+        let point2 = vec![4., 1., -2.];
+
+        assert_eq!(Manhattan::default().distance(&point1, &point2), 7.);
+        assert_eq!(Manhattan::default().distance_squared(&point1, &point2), 49.);
+        assert_eq!(Chebyshev::default().distance(&point1, &point2), 4.);
+        assert_eq!(Minkowski::new(1.).unwrap().distance(&point1, &point2), 7.);
+        assert_eq!(Minkowski::new(2.).unwrap().distance(&point1, &point2), 5.);
+        let minkowski = Minkowski::new(f64::INFINITY).unwrap();
+        assert_eq!(minkowski.distance(&point1, &point2), 4.);
+        assert_eq!(Manhattan::default().distance(&point1, &[0.]), f64::INFINITY);
+
+        assert!(Minkowski::new(0.5).is_err());
+        assert!(Minkowski::new(f64::NAN).is_err());
+    }
+
+    #[test]
+    pub fn test_weighted_euclidean() {
+        let point1 = vec![1., 5., -2.];
+        let point2 = vec![4., 1., -2.];
+
+        let weighted = WeightedEuclidean::new(vec![4., 0., 1.]).unwrap();
+        assert_eq!(weighted.distance(&point1, &point2), 6.);
+        assert_eq!(weighted.distance(&point1, &[1., 5.]), f64::INFINITY);
+        assert!(WeightedEuclidean::new(vec![1., -1.]).is_err());
+
+        let points = vec![point1, point2];
+        assert!(SRTree::new(&points, Params::default_params(), weighted).is_ok());
+        let weighted = WeightedEuclidean::new(vec![1., 1.]).unwrap();
+        let result = SRTree::new(&points, Params::default_params(), weighted);
+        assert!(matches!(result, Err(Error::InvalidMetric(_))));
+    }
 }
@@ -1,4 +1,4 @@
-use crate::SRTree;
+use crate::{node::Node, SRTree};
 use ordered_float::Float;
 
 use super::distance::Metric;
@@ -10,36 +10,38 @@ where
 {
     #[must_use]
     pub fn calculate_mean(&self, node_index: usize) -> Vec<T> {
-        if self.nodes[node_index].is_leaf() {
-            self.calculate_leaf_mean(node_index)
+        let node = &self.nodes[node_index];
+        if node.is_leaf() {
+            self.calculate_points_mean(node.points())
         } else {
-            self.calculate_node_mean(node_index)
+            let children: Vec<&Node<T>> = node
+                .children()
+                .iter()
+                .map(|child_index| &self.nodes[*child_index])
+                .collect();
+            self.calculate_nodes_mean(&children)
         }
     }
 
-    fn calculate_leaf_mean(&self, leaf_index: usize) -> Vec<T> {
-        let leaf = &self.nodes[leaf_index];
+    pub(crate) fn calculate_points_mean(&self, point_indices: &[usize]) -> Vec<T> {
         let mut mean = vec![T::zero(); self.params.dimension];
-        for point_index in leaf.points() {
-            let point = &self.points[*point_index];
+        for point_index in point_indices {
+            let coords = self.coords_of(*point_index);
             for (axis_index, m) in mean.iter_mut().enumerate() {
-                *m = *m + point.coords[axis_index];
+                *m = *m + coords[axis_index];
             }
         }
-        let number_of_points = T::from(leaf.immed_children()).unwrap();
+        let number_of_points = T::from(point_indices.len()).unwrap();
         for m in &mut mean {
             *m = *m / number_of_points;
         }
         mean
     }
 
-    fn calculate_node_mean(&self, node_index: usize) -> Vec<T> {
-        let root = &self.nodes[node_index];
+    pub(crate) fn calculate_nodes_mean(&self, children: &[&Node<T>]) -> Vec<T> {
         let mut number_of_entries = T::zero();
         let mut mean = vec![T::zero(); self.params.dimension];
-        for i in 0..root.immed_children() {
-            let child_index = root.children()[i];
-            let child = &self.nodes[child_index];
+        for child in children {
             let child_number_of_entries = T::from(child.immed_children()).unwrap();
 
             for (axis_index, m) in mean.iter_mut().enumerate() {
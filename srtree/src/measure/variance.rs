@@ -10,28 +10,48 @@ where
 {
     #[must_use]
     pub fn calculate_points_variance(&self, point_indices: &[usize]) -> Vec<T> {
+        self.calculate_variance(point_indices, |point_index| self.coords_of(point_index))
+    }
+
+    /// Calculates the variance of the centroids of the given nodes along every axis.
+    #[must_use]
+    pub fn calculate_nodes_variance(&self, node_indices: &[usize]) -> Vec<T> {
+        self.calculate_variance(node_indices, |node_index| {
+            self.nodes[node_index].sphere.center.coords.as_slice()
+        })
+    }
+
+    fn calculate_variance<'a, F>(&self, indices: &[usize], coords_of: F) -> Vec<T>
+    where
+        F: Fn(usize) -> &'a [T],
+        T: 'a,
+    {
         let dimension = self.params.dimension;
         let mut variances = Vec::new();
         for dim in 0..dimension {
-            let variance = self.calculate_dimension_variance(point_indices, dim);
+            let variance = calculate_dimension_variance(indices, dim, &coords_of);
             variances.push(variance);
         }
         variances
     }
+}
 
-    fn calculate_dimension_variance(&self, point_indices: &[usize], dim: usize) -> T {
-        let mut sum = T::zero();
-        let mut sum_sq = T::zero();
-        for point_index in point_indices {
-            let coord = self.points[*point_index].coords[dim];
-            sum = sum + coord;
-            sum_sq = sum_sq + coord * coord;
-        }
-        let n = T::from(point_indices.len()).unwrap();
-        let mean = sum / n;
-        let mean_sq = mean * mean;
-        sum_sq / n - mean_sq
+fn calculate_dimension_variance<'a, T, F>(indices: &[usize], dim: usize, coords_of: &F) -> T
+where
+    T: Float + 'a,
+    F: Fn(usize) -> &'a [T],
+{
+    let mut sum = T::zero();
+    let mut sum_sq = T::zero();
+    for index in indices {
+        let coord = coords_of(*index)[dim];
+        sum = sum + coord;
+        sum_sq = sum_sq + coord * coord;
     }
+    let n = T::from(indices.len()).unwrap();
+    let mean = sum / n;
+    let mean_sq = mean * mean;
+    sum_sq / n - mean_sq
 }
 
 #[cfg(test)]
@@ -0,0 +1,231 @@
+use crate::{
+    algorithm::{access::TreeAccess, query::query_tree, query_radius::query_radius_tree},
+    error::Error,
+    index_file::{FormatError, Header, PersistentMetric, NODE_INTEGERS, POINT_INTEGERS},
+    measure::distance::Metric,
+    stats::QueryStats,
+};
+use memmap2::Mmap;
+use ordered_float::Float;
+use std::{
+    any::TypeId,
+    fs::File,
+    marker::PhantomData,
+    mem::{align_of, size_of},
+    path::Path,
+    slice,
+};
+
+// offsets of the entry range within a node record
+const FIRST_ENTRY: usize = 3 * 8;
+const NUMBER_OF_ENTRIES: usize = 4 * 8;
+
+/// A read-only tree answering queries directly from a memory-mapped index file written by
+/// [`SRTree::save`](crate::SRTree::save), without loading its nodes and points into memory.
+pub struct MmapSRTree<T, M> {
+    mmap: Mmap,
+    header: Header,
+    metric: M,
+    scalar: PhantomData<T>,
+}
+
+impl<T, M> MmapSRTree<T, M>
+where
+    T: Float + Send + Sync + 'static,
+    M: PersistentMetric<T>,
+{
+    /// Maps an index file into memory.
+    ///
+    /// The file is scanned once to verify its checksum and structure. It must not be modified
+    /// while it is mapped.
+    ///
+    /// # Errors
+    /// * Any error [`SRTree::load`](crate::SRTree::load) returns for the same file.
+    /// * `Error::Format` with `FormatError::Unmappable` if the coordinates cannot be used in
+    ///   place, which requires `f32` or `f64` coordinates on a little-endian target.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        if TypeId::of::<T>() != TypeId::of::<f32>() && TypeId::of::<T>() != TypeId::of::<f64>() {
+            return Err(Error::Format(FormatError::Unmappable(
+                "coordinates must be f32 or f64",
+            )));
+        }
+        if cfg!(target_endian = "big") {
+            return Err(Error::Format(FormatError::Unmappable(
+                "target is big-endian",
+            )));
+        }
+
+        let file = File::open(path)?;
+        // SAFETY: the mapping is only read, and the file is required not to change meanwhile.
+        let mmap = unsafe { Mmap::map(&file)? };
+        let header = Header::parse::<T, M>(&mmap)?;
+        let metric = M::from_parameters(&header.metric_parameters, header.params.dimension)
+            .ok_or(FormatError::InvalidMetricParameters)?;
+        // every scalar is at a multiple of its size from the coordinate block
+        if (mmap.as_ptr() as usize + header.coordinates_offset) % align_of::<T>() != 0 {
+            return Err(Error::Format(FormatError::Unmappable(
+                "coordinates are not aligned",
+            )));
+        }
+        for point_index in 0..header.num_points {
+            header.read_point::<T>(&mmap, point_index)?;
+        }
+        for node_index in 0..header.num_nodes {
+            let record = header.read_node(&mmap, node_index)?;
+            header.read_entries(&mmap, &record)?;
+        }
+
+        Ok(MmapSRTree {
+            mmap,
+            header,
+            metric,
+            scalar: PhantomData,
+        })
+    }
+
+    pub fn query(&self, point_coords: &[T], k: usize) -> (Vec<usize>, Vec<T>) {
+        query_tree(self, point_coords, k, &|_| true, &mut QueryStats::default())
+    }
+
+    pub fn query_radius(&self, point_coords: &[T], radius: T) -> Vec<usize> {
+        query_radius_tree(self, point_coords, radius)
+    }
+}
+
+impl<T, M> MmapSRTree<T, M>
+where
+    T: Float,
+{
+    fn scalars(&self, offset: usize, len: usize) -> &[T] {
+        let bytes = &self.mmap[offset..offset + len * size_of::<T>()];
+        // SAFETY: `open` made sure that `T` is `f32` or `f64` stored in native byte order, and
+        // that scalars are aligned.
+        unsafe { slice::from_raw_parts(bytes.as_ptr().cast::<T>(), len) }
+    }
+
+    fn integer(&self, offset: usize) -> usize {
+        let bytes = self.mmap[offset..offset + 8].try_into().unwrap();
+        usize::try_from(u64::from_le_bytes(bytes)).unwrap_or(usize::MAX)
+    }
+
+    fn point_offset(&self, point_index: usize) -> usize {
+        self.header.points_offset + point_index * (size_of::<T>() + 8 * POINT_INTEGERS)
+    }
+
+    fn node_offset(&self, node_index: usize) -> usize {
+        self.header.nodes_offset + node_index * self.header.node_size
+    }
+
+    /// Returns the offset of the rect low, rect high, centroid or radius of a node.
+    fn node_scalars_offset(&self, node_index: usize, field: usize) -> usize {
+        let dimension = self.header.params.dimension;
+        self.node_offset(node_index) + 8 * NODE_INTEGERS + field * dimension * size_of::<T>()
+    }
+}
+
+impl<T, M> TreeAccess<T> for MmapSRTree<T, M>
+where
+    T: Float,
+    M: Metric<T>,
+{
+    type Metric = M;
+
+    fn metric(&self) -> &M {
+        &self.metric
+    }
+
+    fn root(&self) -> usize {
+        self.header.root_index
+    }
+
+    fn is_leaf(&self, node_index: usize) -> bool {
+        self.integer(self.node_offset(node_index)) == 1
+    }
+
+    fn num_entries(&self, node_index: usize) -> usize {
+        self.integer(self.node_offset(node_index) + NUMBER_OF_ENTRIES)
+    }
+
+    fn entry(&self, node_index: usize, position: usize) -> usize {
+        let first_entry = self.integer(self.node_offset(node_index) + FIRST_ENTRY);
+        self.integer(self.header.entries_offset + (first_entry + position) * 8)
+    }
+
+    fn center(&self, node_index: usize) -> &[T] {
+        let offset = self.node_scalars_offset(node_index, 2);
+        self.scalars(offset, self.header.params.dimension)
+    }
+
+    fn sphere_radius(&self, node_index: usize) -> T {
+        self.scalars(self.node_scalars_offset(node_index, 3), 1)[0]
+    }
+
+    fn rect_low(&self, node_index: usize) -> &[T] {
+        let offset = self.node_scalars_offset(node_index, 0);
+        self.scalars(offset, self.header.params.dimension)
+    }
+
+    fn rect_high(&self, node_index: usize) -> &[T] {
+        let offset = self.node_scalars_offset(node_index, 1);
+        self.scalars(offset, self.header.params.dimension)
+    }
+
+    fn coords(&self, point_index: usize) -> &[T] {
+        let dimension = self.header.params.dimension;
+        let row = self.integer(self.point_offset(point_index) + size_of::<T>() + 8);
+        let offset = self.header.coordinates_offset + row * dimension * size_of::<T>();
+        self.scalars(offset, dimension)
+    }
+
+    fn point_radius(&self, point_index: usize) -> T {
+        self.scalars(self.point_offset(point_index), 1)[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Euclidean, Params, SRTree};
+    use std::{env, fs};
+
+    #[test]
+    pub fn test_mmap_query() {
+        let points: Vec<Vec<f64>> = (0..500)
+            .map(|i| vec![f64::from(i % 23), f64::from(i % 37), f64::from(i % 11)])
+            .collect();
+        let mut tree = SRTree::euclidean_with_params(&points, Params::new(3, 8).unwrap()).unwrap();
+        for i in (0..500).step_by(7) {
+            tree.remove(i);
+        }
+        let path = env::temp_dir().join(format!("srtree-{}-mmap", std::process::id()));
+        tree.save(&path).unwrap();
+        let mapped = MmapSRTree::<f64, Euclidean>::open(&path).unwrap();
+
+        for query in [[0., 0., 0.], [11.5, 20., 3.], [30., -5., 12.]] {
+            assert_eq!(mapped.query(&query, 10), tree.query(&query, 10));
+            assert_eq!(
+                mapped.query_radius(&query, 6.),
+                tree.query_radius(&query, 6.)
+            );
+        }
+        drop(mapped);
+
+        assert!(matches!(
+            MmapSRTree::<f32, Euclidean>::open(&path),
+            Err(Error::Format(FormatError::ScalarSizeMismatch { .. }))
+        ));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    pub fn test_mmap_empty_tree() {
+        let mut tree = SRTree::euclidean(&[vec![1_f32, 2.]]).unwrap();
+        tree.remove(0);
+        let path = env::temp_dir().join(format!("srtree-{}-mmap-empty", std::process::id()));
+        tree.save(&path).unwrap();
+        let mapped = MmapSRTree::<f32, Euclidean>::open(&path).unwrap();
+        assert!(mapped.query(&[1., 2.], 3).0.is_empty());
+        assert!(mapped.query_radius(&[1., 2.], 1.).is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,11 +1,13 @@
 use crate::shape::{point::Point, rect::Rect, sphere::Sphere};
 use ordered_float::Float;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Data {
     Points(Vec<usize>),
     Nodes(Vec<usize>),
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node<T> {
     pub rect: Rect<T>,
     pub sphere: Sphere<T>,
@@ -57,6 +59,13 @@ where
         }
     }
 
+    pub fn children_mut(&mut self) -> &mut Vec<usize> {
+        match &mut self.data {
+            Data::Nodes(nodes) => nodes,
+            Data::Points(_) => panic!("not a node"),
+        }
+    }
+
     pub fn points(&self) -> &Vec<usize> {
         match &self.data {
             Data::Points(points) => points,
@@ -77,6 +86,12 @@ where
         }
     }
 
+    pub fn set_children(&mut self, children: Vec<usize>) {
+        if !self.is_leaf() {
+            self.data = Data::Nodes(children);
+        }
+    }
+
     pub fn immed_children(&self) -> usize {
         match &self.data {
             Data::Points(pts) => pts.len(),
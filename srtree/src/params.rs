@@ -1,28 +1,68 @@
+use crate::error::Error;
+
+const DEFAULT_REINSERTION_FRACTION: f64 = 0.3;
+
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Params {
     pub min_number_of_elements: usize,
     pub max_number_of_elements: usize,
+    pub reinsertion_fraction: f64,
     pub dimension: usize,
 }
 
 impl Params {
-    #[must_use]
-    pub fn new(min_number_of_elements: usize, max_number_of_elements: usize) -> Option<Params> {
+    /// Creates params with the minimum and maximum numbers of entries per node.
+    ///
+    /// # Errors
+    /// * `Error::InvalidParams` if the maximum is less than 2, in which case an overflowing node
+    ///   cannot be split into two nodes that fit.
+    /// * `Error::InvalidParams` if the minimum is more than half of the maximum, rounded up, in
+    ///   which case a split node cannot always fill both halves.
+    pub fn new(
+        min_number_of_elements: usize,
+        max_number_of_elements: usize,
+    ) -> Result<Params, Error> {
+        if max_number_of_elements < 2 {
+            return Err(Error::InvalidParams(
+                "maximum number of elements must be at least 2",
+            ));
+        }
         if min_number_of_elements > (max_number_of_elements + 1) / 2 {
-            return None;
+            return Err(Error::InvalidParams(
+                "minimum number of elements exceeds half of the maximum",
+            ));
         }
-        Some(Params {
+        Ok(Params {
             min_number_of_elements,
             max_number_of_elements,
+            reinsertion_fraction: DEFAULT_REINSERTION_FRACTION,
             dimension: 0,
         })
     }
 
+    /// Sets the fraction of entries that an overflowing node reinserts before it is split.
+    ///
+    /// A fraction of `0` disables forced reinsertion, so that overflowing nodes are always split.
+    ///
+    /// # Errors
+    /// * `Error::InvalidParams` if the fraction is not in `[0, 1)`.
+    pub fn with_reinsertion_fraction(mut self, reinsertion_fraction: f64) -> Result<Params, Error> {
+        if !(0. ..1.).contains(&reinsertion_fraction) {
+            return Err(Error::InvalidParams(
+                "reinsertion fraction must be in [0, 1)",
+            ));
+        }
+        self.reinsertion_fraction = reinsertion_fraction;
+        Ok(self)
+    }
+
     #[must_use]
     pub fn default_params() -> Params {
         Params {
             min_number_of_elements: 8,
             max_number_of_elements: 20,
+            reinsertion_fraction: DEFAULT_REINSERTION_FRACTION,
             dimension: 0,
         }
     }
@@ -37,7 +77,14 @@ mod tests {
         let min_num_of_elements_per_node = 6;
         let max_num_of_elements_per_node = 10;
         let params = Params::new(min_num_of_elements_per_node, max_num_of_elements_per_node);
-        assert!(params.is_none())
+        assert!(matches!(params, Err(Error::InvalidParams(_))));
+    }
+
+    #[test]
+    pub fn test_with_invalid_max_num_elements() {
+        assert!(matches!(Params::new(0, 1), Err(Error::InvalidParams(_))));
+        assert!(matches!(Params::new(1, 1), Err(Error::InvalidParams(_))));
+        assert!(Params::new(1, 2).is_ok());
     }
 
     #[test]
@@ -45,6 +92,16 @@ mod tests {
         let min_num_of_elements_per_node = 4;
         let max_num_of_elements_per_node = 10;
         let params = Params::new(min_num_of_elements_per_node, max_num_of_elements_per_node);
-        assert!(params.is_some())
+        assert!(params.is_ok())
+    }
+
+    #[test]
+    pub fn test_with_reinsertion_fraction() {
+        let params = Params::default_params();
+        assert!(params.with_reinsertion_fraction(0.).is_ok());
+        assert!(params.with_reinsertion_fraction(0.5).is_ok());
+        assert!(params.with_reinsertion_fraction(1.).is_err());
+        assert!(params.with_reinsertion_fraction(-0.1).is_err());
+        assert!(params.with_reinsertion_fraction(f64::NAN).is_err());
     }
 }
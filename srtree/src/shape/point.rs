@@ -1,6 +1,7 @@
 use ordered_float::Float;
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point<T> {
     pub coords: Vec<T>,
     pub radius: T,
@@ -34,3 +35,27 @@ where
         self.coords.len()
     }
 }
+
+/// A point stored in a tree. Its coordinates are kept in the coordinate buffer of the tree, at
+/// the given row.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointEntry<T> {
+    pub row: usize,
+    /// Distance from the centroid of the leaf holding the point.
+    pub radius: T,
+    pub parent_index: usize,
+}
+
+impl<T> PointEntry<T>
+where
+    T: Float + Send + Sync,
+{
+    pub fn new(row: usize) -> PointEntry<T> {
+        PointEntry {
+            row,
+            radius: T::zero(),
+            parent_index: usize::MAX,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::shape::point::Point;
 use ordered_float::Float;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rect<T> {
     pub low: Vec<T>,
     pub high: Vec<T>,
@@ -1,5 +1,5 @@
 use super::{point::Point, rect::Rect, sphere::Sphere};
-use crate::{measure::distance::Metric, SRTree};
+use crate::{measure::distance::Metric, node::Node, SRTree};
 use ordered_float::{Float, OrderedFloat};
 
 impl<T, M> SRTree<T, M>
@@ -8,48 +8,81 @@ where
     M: Metric<T>,
 {
     pub fn reshape(&mut self, node_index: usize) {
-        let centroid = Point::new(self.calculate_mean(node_index), node_index);
         let node = &self.nodes[node_index];
-
-        let mut max_distance = T::zero();
-        let mut low = centroid.coords.clone();
-        let mut high = centroid.coords.clone();
-        if node.is_leaf() {
-            let mut points = Vec::with_capacity(node.points().len());
-            for point_index in node.points() {
-                let point = &self.points[*point_index];
-                for i in 0..low.len() {
-                    low[i] = low[i].min(point.coords[i]);
-                    high[i] = high[i].max(point.coords[i]);
-                }
-                let distance_to_point = self.distance(&centroid, point);
-                max_distance = max_distance.max(distance_to_point);
-                points.push((distance_to_point, *point_index));
-            }
-
+        let (rect, sphere) = if node.is_leaf() {
+            let (rect, sphere, points) = self.leaf_shape(node.points(), node_index);
             for (distance, point_index) in &points {
                 self.points[*point_index].radius = *distance;
                 self.points[*point_index].parent_index = node_index;
             }
-
-            points.sort_by_key(|(distance, _)| -OrderedFloat(*distance));
             let points: Vec<usize> = points.into_iter().map(|(_, index)| index).collect();
             self.nodes[node_index].set_points(points);
+            (rect, sphere)
         } else {
-            node.children().iter().for_each(|child_index| {
-                let child = &self.nodes[*child_index];
-                for i in 0..self.params.dimension {
-                    low[i] = low[i].min(child.rect.low[i]);
-                    high[i] = high[i].max(child.rect.high[i]);
-                }
-                let distance = self.point_to_node_max_distance(&centroid, child);
-                max_distance = max_distance.max(distance);
-            });
-        }
+            let children: Vec<&Node<T>> = node
+                .children()
+                .iter()
+                .map(|child_index| &self.nodes[*child_index])
+                .collect();
+            self.node_shape(&children, node_index)
+        };
 
         let node = &mut self.nodes[node_index];
-        node.rect = Rect::new(low, high);
-        node.sphere = Sphere::new(centroid, max_distance);
+        node.rect = rect;
+        node.sphere = sphere;
+    }
+
+    /// Calculates the bounding shapes of a leaf holding the given points, along with the
+    /// distances of the points from the centroid, sorted in descending order.
+    pub(crate) fn leaf_shape(
+        &self,
+        point_indices: &[usize],
+        node_index: usize,
+    ) -> (Rect<T>, Sphere<T>, Vec<(T, usize)>) {
+        let centroid = Point::new(self.calculate_points_mean(point_indices), node_index);
+
+        let mut max_distance = T::zero();
+        let mut low = centroid.coords.clone();
+        let mut high = centroid.coords.clone();
+        let mut points = Vec::with_capacity(point_indices.len());
+        for point_index in point_indices {
+            let coords = self.coords_of(*point_index);
+            for i in 0..low.len() {
+                low[i] = low[i].min(coords[i]);
+                high[i] = high[i].max(coords[i]);
+            }
+            let distance_to_point = self.metric.distance(&centroid.coords, coords);
+            max_distance = max_distance.max(distance_to_point);
+            points.push((distance_to_point, *point_index));
+        }
+        points.sort_by_key(|(distance, _)| -OrderedFloat(*distance));
+        (
+            Rect::new(low, high),
+            Sphere::new(centroid, max_distance),
+            points,
+        )
+    }
+
+    /// Calculates the bounding shapes of an internal node with the given children.
+    pub(crate) fn node_shape(
+        &self,
+        children: &[&Node<T>],
+        node_index: usize,
+    ) -> (Rect<T>, Sphere<T>) {
+        let centroid = Point::new(self.calculate_nodes_mean(children), node_index);
+
+        let mut max_distance = T::zero();
+        let mut low = centroid.coords.clone();
+        let mut high = centroid.coords.clone();
+        for child in children {
+            for i in 0..self.params.dimension {
+                low[i] = low[i].min(child.rect.low[i]);
+                high[i] = high[i].max(child.rect.high[i]);
+            }
+            let distance = self.point_to_node_max_distance(&centroid, child);
+            max_distance = max_distance.max(distance);
+        }
+        (Rect::new(low, high), Sphere::new(centroid, max_distance))
     }
 }
 
@@ -1,6 +1,7 @@
 use super::point::Point;
 use ordered_float::Float;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sphere<T> {
     pub center: Point<T>,
     pub radius: T,
@@ -1,23 +1,75 @@
 use std::cmp::Ordering;
 
+use crate::error::Error;
 use crate::measure::distance::{Euclidean, Metric};
 use crate::node::Node;
 use crate::params::Params;
-use crate::shape::point::Point;
+use crate::shape::point::PointEntry;
 use ordered_float::Float;
 
-#[derive(Debug)]
-pub enum ArrayError {
-    Empty,
-    DimensionMismatch,
-}
-
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "SRTreeFields<T, M>",
+        bound(deserialize = "T: Float + Send + Sync + serde::Deserialize<'de>, \
+                           M: Metric<T> + serde::Deserialize<'de>")
+    )
+)]
 pub struct SRTree<T, M> {
     pub root_index: usize,
-    pub points: Vec<Point<T>>,
+    /// Coordinates of all the points, row by row. Points of the same leaf are stored in adjacent
+    /// rows after bulk-loading.
+    pub coords: Vec<T>,
+    pub points: Vec<PointEntry<T>>,
     pub nodes: Vec<Node<T>>,
     pub params: Params,
     pub metric: M,
+    /// Number of points stored in leaves, excluding removed ones.
+    pub(crate) num_points: usize,
+}
+
+/// Deserialized form of [`SRTree`], checked against the dimension of its metric.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SRTreeFields<T, M> {
+    root_index: usize,
+    coords: Vec<T>,
+    points: Vec<PointEntry<T>>,
+    nodes: Vec<Node<T>>,
+    params: Params,
+    metric: M,
+    num_points: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<T, M> TryFrom<SRTreeFields<T, M>> for SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    type Error = Error;
+
+    fn try_from(fields: SRTreeFields<T, M>) -> Result<Self, Error> {
+        if fields
+            .metric
+            .dimension()
+            .map_or(false, |expected| expected != fields.params.dimension)
+        {
+            return Err(Error::InvalidMetric(
+                "metric is defined for another dimension than the points",
+            ));
+        }
+        Ok(SRTree {
+            root_index: fields.root_index,
+            coords: fields.coords,
+            points: fields.points,
+            nodes: fields.nodes,
+            params: fields.params,
+            metric: fields.metric,
+            num_points: fields.num_points,
+        })
+    }
 }
 
 impl<T, M> SRTree<T, M>
@@ -28,40 +80,180 @@ where
     /// Builds `SRTree` with the given points, params and metric.
     ///
     /// # Errors
-    /// * `ArrayError::Empty` if the input array is empty.
-    /// * `ArrayError::DimensionMismatch` if the input array contains points of different dimensions.
-    pub fn new(pts: &[Vec<T>], mut params: Params, metric: M) -> Result<Self, ArrayError> {
-        if pts.is_empty() {
-            return Err(ArrayError::Empty);
+    /// * `Error::Empty` if the input array is empty.
+    /// * `Error::DimensionMismatch` if the input array contains points of different dimensions.
+    /// * `Error::NonFiniteCoordinate` if a point has a NaN or infinite coordinate.
+    /// * `Error::InvalidMetric` if the metric is defined for another dimension than the points.
+    pub fn new(pts: &[Vec<T>], params: Params, metric: M) -> Result<Self, Error> {
+        SRTree::from_iter(pts, params, metric)
+    }
+
+    /// Builds `SRTree` like [`SRTree::new`], taking ownership of the points and releasing each
+    /// of them as soon as it is copied into the tree.
+    ///
+    /// # Errors
+    /// * `Error::Empty` if the input array is empty.
+    /// * `Error::DimensionMismatch` if the input array contains points of different dimensions.
+    /// * `Error::NonFiniteCoordinate` if a point has a NaN or infinite coordinate.
+    /// * `Error::InvalidMetric` if the metric is defined for another dimension than the points.
+    pub fn from_vecs(pts: Vec<Vec<T>>, params: Params, metric: M) -> Result<Self, Error> {
+        SRTree::from_iter(pts, params, metric)
+    }
+
+    /// Builds `SRTree` with points read one at a time from an iterator, so that the input does
+    /// not have to be collected first.
+    ///
+    /// # Errors
+    /// * `Error::Empty` if the iterator yields no point.
+    /// * `Error::DimensionMismatch` if a point does not have the same dimension as the first
+    ///   one.
+    /// * `Error::NonFiniteCoordinate` if a point has a NaN or infinite coordinate.
+    /// * `Error::InvalidMetric` if the metric is defined for another dimension than the points.
+    pub fn from_iter<I>(pts: I, params: Params, metric: M) -> Result<Self, Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[T]>,
+    {
+        let (coords, dimension, num_points) = flatten(pts, None)?;
+        let mut tree = SRTree::without_nodes(coords, dimension, num_points, params, metric)?;
+        tree.build_nodes((0..num_points).collect());
+        Ok(tree)
+    }
+
+    /// Builds `SRTree` like [`SRTree::from_iter`], leaving out the points that have a NaN or
+    /// infinite coordinate instead of failing, and returns it with the rows of those points.
+    ///
+    /// Points keep the index of their row, so the rows left out are not in the tree, as if they
+    /// had been removed.
+    ///
+    /// # Errors
+    /// * `Error::Empty` if the iterator yields no point.
+    /// * `Error::DimensionMismatch` if a point does not have the same dimension as the first
+    ///   one.
+    /// * `Error::InvalidMetric` if the metric is defined for another dimension than the points.
+    pub fn new_skipping_invalid<I>(
+        pts: I,
+        params: Params,
+        metric: M,
+    ) -> Result<(Self, Vec<usize>), Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[T]>,
+    {
+        let mut invalid_rows = Vec::new();
+        let (coords, dimension, num_points) = flatten(pts, Some(&mut invalid_rows))?;
+        let mut tree = SRTree::without_nodes(coords, dimension, num_points, params, metric)?;
+        let mut invalid = invalid_rows.iter().peekable();
+        let point_indices = (0..num_points)
+            .filter(|row| invalid.next_if_eq(&row).is_none())
+            .collect();
+        tree.build_nodes(point_indices);
+        Ok((tree, invalid_rows))
+    }
+
+    /// Builds `SRTree` from the coordinates of points laid out one after another in a single
+    /// slice, such as the buffer of a row-major matrix.
+    ///
+    /// # Errors
+    /// * `Error::Empty` if there are no coordinates.
+    /// * `Error::DimensionMismatch` if `dimension` is zero or the number of coordinates is
+    ///   not a multiple of `dimension`.
+    /// * `Error::NonFiniteCoordinate` if a coordinate is NaN or infinite.
+    /// * `Error::InvalidMetric` if the metric is defined for another dimension than the points.
+    pub fn from_flat(
+        coords: &[T],
+        dimension: usize,
+        params: Params,
+        metric: M,
+    ) -> Result<Self, Error> {
+        if coords.is_empty() {
+            return Err(Error::Empty);
         }
-        params.dimension = pts[0].len();
-        if !pts.iter().all(|p| p.len() == params.dimension) {
-            return Err(ArrayError::DimensionMismatch);
+        if dimension == 0 || coords.len() % dimension != 0 {
+            return Err(Error::DimensionMismatch {
+                row: coords.len().checked_div(dimension).unwrap_or(0),
+                expected: dimension,
+                found: coords.len().checked_rem(dimension).unwrap_or(coords.len()),
+            });
         }
+        let num_points = coords.len() / dimension;
+        if let Some(position) = coords.iter().position(|coord| !coord.is_finite()) {
+            return Err(Error::NonFiniteCoordinate {
+                row: position / dimension,
+                column: position % dimension,
+            });
+        }
+        let mut tree =
+            SRTree::without_nodes(coords.to_vec(), dimension, num_points, params, metric)?;
+        tree.build_nodes((0..num_points).collect());
+        Ok(tree)
+    }
 
-        let points: Vec<Point<T>> = pts
-            .iter()
-            .enumerate()
-            .map(|(i, p)| Point::new(p.clone(), i))
-            .collect();
-        let point_indices = (0..points.len()).collect();
-        let mut tree = SRTree {
+    fn without_nodes(
+        coords: Vec<T>,
+        dimension: usize,
+        num_points: usize,
+        mut params: Params,
+        metric: M,
+    ) -> Result<Self, Error> {
+        if metric
+            .dimension()
+            .map_or(false, |expected| expected != dimension)
+        {
+            return Err(Error::InvalidMetric(
+                "metric is defined for another dimension than the points",
+            ));
+        }
+        params.dimension = dimension;
+        let points = (0..num_points).map(PointEntry::new).collect();
+        Ok(SRTree {
             root_index: usize::MAX,
+            coords,
             points,
             nodes: Vec::new(),
             params,
             metric,
-        };
-        tree.root_index = tree.bulk_load(point_indices);
+            num_points: 0,
+        })
+    }
+
+    fn build_nodes(&mut self, point_indices: Vec<usize>) {
+        self.num_points = point_indices.len();
+        self.root_index = self.bulk_load(point_indices);
+        self.reorder_rows();
+    }
+
+    /// Builds `SRTree` like [`SRTree::new`], loading independent subtrees in parallel.
+    ///
+    /// The resulting tree is identical to the one built by [`SRTree::new`].
+    ///
+    /// # Errors
+    /// * `Error::Empty` if the input array is empty.
+    /// * `Error::DimensionMismatch` if the input array contains points of different dimensions.
+    /// * `Error::NonFiniteCoordinate` if a point has a NaN or infinite coordinate.
+    /// * `Error::InvalidMetric` if the metric is defined for another dimension than the points.
+    #[cfg(feature = "rayon")]
+    pub fn par_new(pts: &[Vec<T>], params: Params, metric: M) -> Result<Self, Error>
+    where
+        M: Sync,
+    {
+        let (coords, dimension, num_points) = flatten(pts, None)?;
+        let mut tree = SRTree::without_nodes(coords, dimension, num_points, params, metric)?;
+        let point_indices = (0..tree.points.len()).collect();
+        tree.num_points = num_points;
+        tree.root_index = tree.par_bulk_load(point_indices);
+        tree.reorder_rows();
         Ok(tree)
     }
 
     /// Builds `SRTree` with the given points (using default params) and metric.
     ///
     /// # Errors
-    /// * `ArrayError::Empty` if the input array is empty.
-    /// * `ArrayError::DimensionMismatch` if the input array contains points of different dimensions.
-    pub fn default(pts: &[Vec<T>], metric: M) -> Result<Self, ArrayError> {
+    /// * `Error::Empty` if the input array is empty.
+    /// * `Error::DimensionMismatch` if the input array contains points of different dimensions.
+    /// * `Error::NonFiniteCoordinate` if a point has a NaN or infinite coordinate.
+    /// * `Error::InvalidMetric` if the metric is defined for another dimension than the points.
+    pub fn default(pts: &[Vec<T>], metric: M) -> Result<Self, Error> {
         SRTree::new(pts, Params::default_params(), metric)
     }
 
@@ -71,12 +263,65 @@ where
         index
     }
 
+    /// Returns the coordinates of the point with the given index.
+    pub fn coords_of(&self, point_index: usize) -> &[T] {
+        let row = self.points[point_index].row;
+        let dimension = self.params.dimension;
+        &self.coords[row * dimension..(row + 1) * dimension]
+    }
+
+    pub(crate) fn coords_of_mut(&mut self, point_index: usize) -> &mut [T] {
+        let row = self.points[point_index].row;
+        let dimension = self.params.dimension;
+        &mut self.coords[row * dimension..(row + 1) * dimension]
+    }
+
+    /// Appends a point to the coordinate buffer and returns its index, without placing it in a
+    /// leaf.
+    pub(crate) fn push_point(&mut self, coords: Vec<T>) -> usize {
+        let point_index = self.points.len();
+        // rows are a permutation of point indices, so the next row is the next index
+        self.points.push(PointEntry::new(point_index));
+        self.coords.extend(coords);
+        point_index
+    }
+
+    /// Checks the coordinates of a point that is added to the tree as the point at `row`.
+    pub(crate) fn check_point(&self, row: usize, coords: &[T]) -> Result<(), Error> {
+        if coords.len() != self.params.dimension {
+            return Err(Error::DimensionMismatch {
+                row,
+                expected: self.params.dimension,
+                found: coords.len(),
+            });
+        }
+        if let Some(column) = coords.iter().position(|coord| !coord.is_finite()) {
+            return Err(Error::NonFiniteCoordinate { row, column });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_query_dimension(&self, point_coords: &[T]) -> Result<(), Error> {
+        if point_coords.len() != self.params.dimension {
+            return Err(Error::QueryDimensionMismatch {
+                expected: self.params.dimension,
+                found: point_coords.len(),
+            });
+        }
+        Ok(())
+    }
+
     pub fn num_nodes(&self) -> usize {
         self.nodes.len()
     }
 
     pub fn num_points(&self) -> usize {
-        self.points.len()
+        self.num_points
+    }
+
+    /// Returns `true` if the point with the given index is stored in the tree.
+    pub fn contains(&self, point_index: usize) -> bool {
+        point_index < self.points.len() && self.points[point_index].parent_index != usize::MAX
     }
 
     pub fn num_leaves(&self) -> usize {
@@ -84,6 +329,9 @@ where
     }
 
     pub fn height(&self) -> usize {
+        if self.root_index == usize::MAX {
+            return 0;
+        }
         self.nodes[self.root_index].height
     }
 
@@ -117,6 +365,50 @@ where
     }
 }
 
+/// Copies the points into a single buffer, checking their dimensions and coordinates on the
+/// way, and returns it with the dimension and the number of points.
+///
+/// Points with a NaN or infinite coordinate are rejected, unless `invalid_rows` is given, in
+/// which case their rows are collected into it.
+fn flatten<T, I>(
+    pts: I,
+    mut invalid_rows: Option<&mut Vec<usize>>,
+) -> Result<(Vec<T>, usize, usize), Error>
+where
+    T: Float,
+    I: IntoIterator,
+    I::Item: AsRef<[T]>,
+{
+    let mut pts = pts.into_iter().peekable();
+    let dimension = pts.peek().ok_or(Error::Empty)?.as_ref().len();
+    let mut coords = Vec::with_capacity(dimension * pts.size_hint().0);
+    let mut num_points = 0;
+    for point in pts {
+        let point = point.as_ref();
+        if point.len() != dimension {
+            return Err(Error::DimensionMismatch {
+                row: num_points,
+                expected: dimension,
+                found: point.len(),
+            });
+        }
+        if let Some(column) = point.iter().position(|coord| !coord.is_finite()) {
+            match invalid_rows.as_mut() {
+                Some(invalid_rows) => invalid_rows.push(num_points),
+                None => {
+                    return Err(Error::NonFiniteCoordinate {
+                        row: num_points,
+                        column,
+                    })
+                }
+            }
+        }
+        coords.extend_from_slice(point);
+        num_points += 1;
+    }
+    Ok((coords, dimension, num_points))
+}
+
 impl<T> SRTree<T, Euclidean>
 where
     T: Float + Send + Sync,
@@ -124,25 +416,27 @@ where
     /// Builds `SRTree` with the given points, params and Euclidean metric.
     ///
     /// # Errors
-    /// * `ArrayError::Empty` if the input array is empty.
-    /// * `ArrayError::DimensionMismatch` if the input array contains points of different dimensions.
-    pub fn euclidean_with_params(pts: &[Vec<T>], params: Params) -> Result<Self, ArrayError> {
+    /// * `Error::Empty` if the input array is empty.
+    /// * `Error::DimensionMismatch` if the input array contains points of different dimensions.
+    /// * `Error::NonFiniteCoordinate` if a point has a NaN or infinite coordinate.
+    pub fn euclidean_with_params(pts: &[Vec<T>], params: Params) -> Result<Self, Error> {
         SRTree::new(pts, params, Euclidean::default())
     }
 
     /// Builds `SRTree` with the given points (using default params) and Euclidean metric.
     ///
     /// # Errors
-    /// * `ArrayError::Empty` if the input array is empty.
-    /// * `ArrayError::DimensionMismatch` if the input array contains points of different dimensions.
-    pub fn euclidean(pts: &[Vec<T>]) -> Result<Self, ArrayError> {
+    /// * `Error::Empty` if the input array is empty.
+    /// * `Error::DimensionMismatch` if the input array contains points of different dimensions.
+    /// * `Error::NonFiniteCoordinate` if a point has a NaN or infinite coordinate.
+    pub fn euclidean(pts: &[Vec<T>]) -> Result<Self, Error> {
         SRTree::default(pts, Euclidean::default())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::SRTree;
+    use crate::{Error, Euclidean, Params, SRTree};
 
     #[test]
     pub fn test_empty_input() {
@@ -153,9 +447,16 @@ mod tests {
 
     #[test]
     pub fn test_dimension_mismatch() {
-        let pts = vec![vec![1.0, 2.0], vec![3.0]];
+        let pts = vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0]];
         let tree = SRTree::euclidean(&pts);
-        assert!(tree.is_err());
+        assert!(matches!(
+            tree,
+            Err(Error::DimensionMismatch {
+                row: 2,
+                expected: 2,
+                found: 1
+            })
+        ));
     }
 
     #[test]
@@ -165,6 +466,106 @@ mod tests {
         assert!(tree.is_ok());
     }
 
+    #[test]
+    pub fn test_from_flat() {
+        let coords = [0., 0., 1., 1., 2., 2.];
+        let tree = SRTree::from_flat(&coords, 2, Params::default_params(), Euclidean::default())
+            .expect("Failed to build SRTree");
+        assert_eq!(tree.num_points(), 3);
+        assert_eq!(tree.coords_of(2), [2., 2.]);
+        assert_eq!(tree.query(&[1.2, 1.2], 1).0, vec![1]);
+
+        let tree = SRTree::from_flat(&coords, 4, Params::default_params(), Euclidean::default());
+        assert!(matches!(
+            tree,
+            Err(Error::DimensionMismatch {
+                row: 1,
+                expected: 4,
+                found: 2
+            })
+        ));
+        let tree = SRTree::from_flat(&coords, 0, Params::default_params(), Euclidean::default());
+        assert!(matches!(
+            tree,
+            Err(Error::DimensionMismatch {
+                row: 0,
+                expected: 0,
+                found: 6
+            })
+        ));
+        let tree =
+            SRTree::<f64, _>::from_flat(&[], 2, Params::default_params(), Euclidean::default());
+        assert!(matches!(tree, Err(Error::Empty)));
+    }
+
+    #[test]
+    pub fn test_from_vecs_and_iter() {
+        let pts = vec![vec![0., 0.], vec![1., 1.], vec![2., 2.]];
+        let tree = SRTree::from_vecs(pts, Params::default_params(), Euclidean::default())
+            .expect("Failed to build SRTree");
+        assert_eq!(tree.num_points(), 3);
+        assert_eq!(tree.query(&[1.8, 1.8], 1).0, vec![2]);
+
+        let pts = (0..100).map(|i| [f64::from(i), f64::from(i)]);
+        let tree = SRTree::from_iter(pts, Params::default_params(), Euclidean::default())
+            .expect("Failed to build SRTree");
+        assert_eq!(tree.num_points(), 100);
+        assert_eq!(tree.coords_of(42), [42., 42.]);
+
+        let pts = (0..10).map(|i| vec![0.; if i == 7 { 3 } else { 2 }]);
+        let tree = SRTree::<f64, _>::from_iter(pts, Params::default_params(), Euclidean::default());
+        assert!(matches!(
+            tree,
+            Err(Error::DimensionMismatch {
+                row: 7,
+                expected: 2,
+                found: 3
+            })
+        ));
+        let tree = SRTree::<f64, _>::from_iter(
+            Vec::<Vec<f64>>::new(),
+            Params::default_params(),
+            Euclidean::default(),
+        );
+        assert!(matches!(tree, Err(Error::Empty)));
+    }
+
+    #[test]
+    pub fn test_non_finite_coordinates() {
+        let pts = vec![
+            vec![0., 0.],
+            vec![1., f64::NAN],
+            vec![2., 2.],
+            vec![f64::INFINITY, 3.],
+        ];
+        let tree = SRTree::euclidean(&pts);
+        assert!(matches!(
+            tree,
+            Err(Error::NonFiniteCoordinate { row: 1, column: 1 })
+        ));
+        let coords: Vec<f64> = pts.iter().flatten().copied().collect();
+        let tree = SRTree::from_flat(&coords, 2, Params::default_params(), Euclidean::default());
+        assert!(matches!(
+            tree,
+            Err(Error::NonFiniteCoordinate { row: 1, column: 1 })
+        ));
+
+        let (tree, invalid_rows) =
+            SRTree::new_skipping_invalid(&pts, Params::default_params(), Euclidean::default())
+                .expect("Failed to build SRTree");
+        assert_eq!(invalid_rows, vec![1, 3]);
+        assert_eq!(tree.num_points(), 2);
+        assert!(tree.contains(2) && !tree.contains(3));
+        assert_eq!(tree.query(&[2.5, 2.5], 5).0, vec![2, 0]);
+
+        let pts = vec![vec![f64::NAN]; 3];
+        let (tree, invalid_rows) =
+            SRTree::new_skipping_invalid(&pts, Params::default_params(), Euclidean::default())
+                .expect("Failed to build SRTree");
+        assert_eq!(invalid_rows, vec![0, 1, 2]);
+        assert!(tree.query(&[0.], 1).0.is_empty());
+    }
+
     #[test]
     pub fn test_large_input() {
         let mut pts = Vec::new();
@@ -1,94 +1,216 @@
-use crate::{measure::distance::Euclidean, SRTree};
-use num_traits::Float;
-
-static mut NUM_VISITED_POINTS: usize = 0;
-static mut NUM_COMPARED_POINTS: usize = 0;
-static mut NUM_VISITED_NODES: usize = 0;
-static mut NUM_VISITED_LEAVES: usize = 0;
-static mut NUM_COMPARED_NODES: usize = 0;
-static mut NUM_COMPARED_LEAVES: usize = 0;
-
-pub fn reset() {
-    unsafe {
-        NUM_VISITED_POINTS = 0;
-        NUM_COMPARED_POINTS = 0;
-        NUM_VISITED_NODES = 0;
-        NUM_VISITED_LEAVES = 0;
-        NUM_COMPARED_NODES = 0;
-        NUM_COMPARED_LEAVES = 0;
-    }
+use crate::{measure::distance::Metric, node::Node, SRTree};
+use num_traits::cast;
+use ordered_float::Float;
+
+/// Counts of the work done by a single nearest-neighbor search, to measure how well the tree
+/// prunes the search space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueryStats {
+    /// Internal nodes the search descended into.
+    pub visited_nodes: usize,
+    /// Leaves the search descended into.
+    pub visited_leaves: usize,
+    /// Internal nodes whose minimum distance to the query point was computed.
+    pub compared_nodes: usize,
+    /// Leaves whose minimum distance to the query point was computed.
+    pub compared_leaves: usize,
+    /// Points checked against the ball bound of their leaf.
+    pub visited_points: usize,
+    /// Points whose distance to the query point was computed.
+    pub compared_points: usize,
 }
 
-pub fn inc_compared_points(count: usize) {
-    if STATS_ENABLED {
-        unsafe {
-            NUM_COMPARED_POINTS += count;
+impl QueryStats {
+    pub(crate) fn inc_visited_nodes(&mut self, is_leaf: bool) {
+        if is_leaf {
+            self.visited_leaves += 1;
+        } else {
+            self.visited_nodes += 1;
         }
     }
-}
 
-pub fn inc_visited_points() {
-    if STATS_ENABLED {
-        unsafe {
-            NUM_VISITED_POINTS += 1;
+    pub(crate) fn inc_compared_nodes(&mut self, is_leaf: bool) {
+        if is_leaf {
+            self.compared_leaves += 1;
+        } else {
+            self.compared_nodes += 1;
         }
     }
+
+    pub(crate) fn inc_visited_points(&mut self) {
+        self.visited_points += 1;
+    }
+
+    pub(crate) fn inc_compared_points(&mut self) {
+        self.compared_points += 1;
+    }
 }
 
-pub fn inc_compared_nodes(is_leaf: bool) {
-    if STATS_ENABLED {
-        unsafe {
-            if is_leaf {
-                NUM_COMPARED_LEAVES += 1;
-            } else {
-                NUM_COMPARED_NODES += 1;
-            }
+/// Shape and occupancy of the nodes of a tree, returned by [`SRTree::report`].
+#[derive(Clone, Debug)]
+pub struct TreeReport<T> {
+    pub num_points: usize,
+    /// One entry per height, from the leaves to the root.
+    pub levels: Vec<LevelReport<T>>,
+}
+
+/// Shape and occupancy of the nodes of a tree at one height.
+#[derive(Clone, Debug)]
+pub struct LevelReport<T> {
+    /// Height of the nodes, 1 for leaves.
+    pub height: usize,
+    pub num_nodes: usize,
+    /// Mean number of entries per node, relative to `max_number_of_elements`.
+    pub mean_fill: f64,
+    pub min_fill: f64,
+    pub max_fill: f64,
+    pub mean_sphere_radius: T,
+    /// Mean Euclidean length of the diagonals of the rects, whatever the metric of the tree.
+    pub mean_rect_diagonal: T,
+    /// Number of pairs of nodes sharing a parent.
+    pub sibling_pairs: usize,
+    /// Number of sibling pairs whose spheres overlap. Spheres that only touch do not count.
+    pub overlapping_spheres: usize,
+    /// Number of sibling pairs whose rects overlap. Rects that only touch on a face do not
+    /// count, but rects flat in the same dimension at the same coordinate do.
+    pub overlapping_rects: usize,
+    /// Total volume of the intersections of sibling rects.
+    pub rect_overlap_volume: T,
+}
+
+impl<T> LevelReport<T>
+where
+    T: Float,
+{
+    fn new(height: usize) -> Self {
+        LevelReport {
+            height,
+            num_nodes: 0,
+            mean_fill: 0.,
+            min_fill: f64::INFINITY,
+            max_fill: 0.,
+            mean_sphere_radius: T::zero(),
+            mean_rect_diagonal: T::zero(),
+            sibling_pairs: 0,
+            overlapping_spheres: 0,
+            overlapping_rects: 0,
+            rect_overlap_volume: T::zero(),
         }
     }
 }
 
-pub fn inc_visited_nodes(is_leaf: bool) {
-    if STATS_ENABLED {
-        unsafe {
-            if is_leaf {
-                NUM_VISITED_LEAVES += 1;
-            } else {
-                NUM_VISITED_NODES += 1;
+impl<T, M> SRTree<T, M>
+where
+    T: Float + Send + Sync,
+    M: Metric<T>,
+{
+    /// Summarizes the occupancy and shape of the nodes at every height, to compare the trees
+    /// built with different params or loading strategies.
+    ///
+    /// # Panics
+    /// * If a number of nodes or entries cannot be converted to a floating-point number.
+    pub fn report(&self) -> TreeReport<T> {
+        let mut levels: Vec<LevelReport<T>> = (1..=self.height()).map(LevelReport::new).collect();
+        let max_entries: f64 = cast(self.params.max_number_of_elements).unwrap();
+        let mut stack = Vec::new();
+        if self.root_index != usize::MAX {
+            stack.push(self.root_index);
+        }
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            let level = &mut levels[node.height - 1];
+            let fill = cast::<_, f64>(node.immed_children()).unwrap() / max_entries;
+            level.num_nodes += 1;
+            level.mean_fill += fill;
+            level.min_fill = level.min_fill.min(fill);
+            level.max_fill = level.max_fill.max(fill);
+            level.mean_sphere_radius = level.mean_sphere_radius + node.sphere.radius;
+            level.mean_rect_diagonal = level.mean_rect_diagonal + rect_diagonal(node);
+            if node.is_leaf() {
+                continue;
+            }
+
+            let children = node.children();
+            let level = &mut levels[node.height - 2];
+            for (i, child_index) in children.iter().enumerate() {
+                let child = &self.nodes[*child_index];
+                for sibling_index in &children[i + 1..] {
+                    let sibling = &self.nodes[*sibling_index];
+                    level.sibling_pairs += 1;
+                    if self.spheres_intersect(child, sibling) {
+                        level.overlapping_spheres += 1;
+                    }
+                    if let Some(volume) = rect_intersection_volume(child, sibling) {
+                        level.overlapping_rects += 1;
+                        level.rect_overlap_volume = level.rect_overlap_volume + volume;
+                    }
+                }
             }
+            stack.extend(children);
         }
+
+        for level in &mut levels {
+            if level.num_nodes == 0 {
+                continue;
+            }
+            let num_nodes: T = cast(level.num_nodes).unwrap();
+            level.mean_fill /= cast::<_, f64>(level.num_nodes).unwrap();
+            level.mean_sphere_radius = level.mean_sphere_radius / num_nodes;
+            level.mean_rect_diagonal = level.mean_rect_diagonal / num_nodes;
+        }
+        TreeReport {
+            num_points: self.num_points(),
+            levels,
+        }
+    }
+
+    fn spheres_intersect(&self, node: &Node<T>, other: &Node<T>) -> bool {
+        let distance = self
+            .metric
+            .distance(&node.sphere.center.coords, &other.sphere.center.coords);
+        distance < node.sphere.radius + other.sphere.radius
     }
 }
 
-pub fn print<T>(tree: &SRTree<T, Euclidean>)
+/// Returns the Euclidean length of the diagonal of the rect of a node.
+fn rect_diagonal<T>(node: &Node<T>) -> T
 where
     T: Float + Send + Sync,
 {
-    if STATS_ENABLED {
-        unsafe {
-            println!("----------------------");
-            println!("Visited points:  {NUM_VISITED_POINTS}");
-            println!("Compared points: {NUM_COMPARED_POINTS}");
-            println!("Visited leaves:  {NUM_VISITED_LEAVES}");
-            println!("Compared leaves: {NUM_COMPARED_LEAVES}");
-            println!("Visited nodes:   {NUM_VISITED_NODES}");
-            println!("Compared nodes:  {}", NUM_COMPARED_NODES + 1);
-            println!("Total leaves:    {}", tree.num_leaves());
-            println!("Total nodes:     {}", tree.num_nodes() - tree.num_leaves());
-            println!("Tree height:     {}", tree.height());
-            println!("----------------------");
+    node.rect
+        .low
+        .iter()
+        .zip(&node.rect.high)
+        .fold(T::zero(), |sum, (low, high)| sum + (*high - *low).powi(2))
+        .sqrt()
+}
+
+/// Returns the volume of the intersection of the rects of two nodes, or `None` if they do not
+/// overlap.
+///
+/// Rects that only touch on a face do not overlap, but rects that are both flat in a dimension,
+/// as with a constant coordinate, overlap if they lie at the same coordinate; the volume of
+/// their intersection is zero.
+fn rect_intersection_volume<T>(node: &Node<T>, other: &Node<T>) -> Option<T>
+where
+    T: Float + Send + Sync,
+{
+    let mut volume = T::one();
+    for i in 0..node.rect.low.len() {
+        let low = node.rect.low[i].max(other.rect.low[i]);
+        let high = node.rect.high[i].min(other.rect.high[i]);
+        let flat = node.rect.low[i] == node.rect.high[i] && other.rect.low[i] == other.rect.high[i];
+        if low > high || (low == high && !flat) {
+            return None;
         }
+        volume = volume * (high - low);
     }
+    Some(volume)
 }
 
-// Step 1: Enable stats
-const STATS_ENABLED: bool = false;
-
 #[cfg(test)]
 mod tests {
-    use crate::{
-        stats::{print, reset},
-        SRTree,
-    };
+    use crate::{Chebyshev, Params, SRTree};
     use rand::{rngs::StdRng, Rng, SeedableRng};
 
     fn generate_uniform_dataset(n: usize, dim: usize) -> Vec<Vec<f64>> {
@@ -104,16 +226,117 @@ mod tests {
         pts
     }
 
-    // Step 2: Run this test separately
     #[test]
-    pub fn test_bulk_load() {
+    pub fn test_query_with_stats() {
         const D: usize = 8; // dimension
         const N: usize = 2000; // number of points
         let pts = generate_uniform_dataset(N, D);
         let tree = SRTree::euclidean(&pts).expect("Failed to build tree");
 
-        reset();
-        tree.query(&pts[0], 15);
-        print(&tree);
+        let (indices, distances, stats) = tree.query_with_stats(&pts[0], 15);
+        assert_eq!((indices, distances), tree.query(&pts[0], 15));
+        // the root is visited without being compared
+        assert!(stats.visited_nodes >= 1 && stats.visited_nodes <= stats.compared_nodes + 1);
+        assert!(stats.visited_leaves >= 1 && stats.visited_leaves < tree.num_leaves());
+        assert!(stats.visited_leaves <= stats.compared_leaves);
+        assert!(stats.compared_points >= 15);
+        assert!(stats.compared_points <= stats.visited_points);
+        assert!(stats.visited_points < N);
+    }
+
+    #[test]
+    pub fn test_report() {
+        let pts: Vec<Vec<f64>> = (0..100).map(|i| vec![f64::from(i), 0.]).collect();
+        let tree = SRTree::euclidean_with_params(&pts, Params::new(2, 5).unwrap())
+            .expect("Failed to build tree");
+        let report = tree.report();
+        assert_eq!(report.num_points, 100);
+        assert_eq!(report.levels.len(), tree.height());
+
+        let leaves = &report.levels[0];
+        assert_eq!(leaves.height, 1);
+        assert_eq!(leaves.num_nodes, tree.num_leaves());
+        let total_fill = leaves.mean_fill * 5. * leaves.num_nodes as f64;
+        assert!((total_fill - 100.).abs() < 1e-9);
+        assert!(leaves.min_fill >= 0.4 && leaves.max_fill <= 1.);
+
+        let root = report.levels.last().unwrap();
+        assert_eq!(root.num_nodes, 1);
+        assert_eq!(root.sibling_pairs, 0);
+        assert_eq!(root.mean_rect_diagonal, 99.);
+        assert_eq!(
+            report
+                .levels
+                .iter()
+                .map(|level| level.num_nodes)
+                .sum::<usize>(),
+            tree.num_nodes()
+        );
+
+        let mut tree = tree;
+        for i in 0..100 {
+            tree.remove(i);
+        }
+        assert!(tree.report().levels.is_empty());
+    }
+
+    #[test]
+    pub fn test_report_overlap() {
+        // two unit squares, far apart
+        let square = [[0., 0.], [1., 0.], [0., 1.], [1., 1.], [0.5, 0.5]];
+        let pts: Vec<Vec<f64>> = square
+            .iter()
+            .map(|p| p.to_vec())
+            .chain(square.iter().map(|p| vec![p[0] + 10., p[1]]))
+            .collect();
+        let mut tree = SRTree::new(&pts, Params::new(2, 5).unwrap(), Chebyshev::default())
+            .expect("Failed to build tree");
+        let (left, right) = (tree.points[0].parent_index, tree.points[5].parent_index);
+        assert_ne!(left, right);
+
+        let leaves = tree.report().levels[0].clone();
+        assert_eq!((leaves.num_nodes, leaves.sibling_pairs), (2, 1));
+        assert_eq!(leaves.overlapping_spheres, 0);
+        assert_eq!(leaves.overlapping_rects, 0);
+        // Euclidean even though the tree uses Chebyshev distance
+        assert_eq!(leaves.mean_rect_diagonal, 2_f64.sqrt());
+
+        // rects and spheres that only touch
+        tree.nodes[right].rect.low[0] = 1.;
+        tree.nodes[right].sphere.radius = 10. - tree.nodes[left].sphere.radius;
+        let leaves = tree.report().levels[0].clone();
+        assert_eq!(leaves.overlapping_spheres, 0);
+        assert_eq!(leaves.overlapping_rects, 0);
+        assert_eq!(leaves.rect_overlap_volume, 0.);
+
+        tree.nodes[right].rect.low[0] = 0.5;
+        tree.nodes[right].sphere.radius = 10.;
+        let leaves = tree.report().levels[0].clone();
+        assert_eq!(leaves.overlapping_spheres, 1);
+        assert_eq!(leaves.overlapping_rects, 1);
+        assert_eq!(leaves.rect_overlap_volume, 0.5);
+    }
+
+    #[test]
+    pub fn test_report_overlap_with_constant_coordinate() {
+        let pts: Vec<Vec<f64>> = (0..10).map(|i| vec![f64::from(i), 0.]).collect();
+        let mut tree = SRTree::euclidean_with_params(&pts, Params::new(2, 5).unwrap())
+            .expect("Failed to build tree");
+        let (left, right) = (tree.points[0].parent_index, tree.points[9].parent_index);
+        assert_ne!(left, right);
+        assert_eq!(tree.report().levels[0].overlapping_rects, 0);
+
+        // touching on the face x = 4, which is a line in two dimensions
+        tree.nodes[right].rect.low[0] = 4.;
+        assert_eq!(tree.report().levels[0].overlapping_rects, 0);
+
+        tree.nodes[right].rect.low[0] = 3.;
+        let leaves = tree.report().levels[0].clone();
+        assert_eq!(leaves.overlapping_rects, 1);
+        assert_eq!(leaves.rect_overlap_volume, 0.);
+
+        tree.nodes[right].rect.low = tree.nodes[left].rect.low.clone();
+        tree.nodes[right].rect.high = tree.nodes[left].rect.high.clone();
+        assert_eq!(tree.report().levels[0].overlapping_rects, 1);
     }
 }
@@ -0,0 +1,217 @@
+use crate::{error::Error, measure::distance::Euclidean, params::Params, SRTree};
+use ordered_float::Float;
+
+/// A mapping of points under which a distance becomes a monotonic function of the Euclidean
+/// distance, so that [`TransformedSRTree`] can search by it.
+pub trait PointTransform<T> {
+    /// Returns the dimension of the points the transform accepts, or `None` for any.
+    fn dimension(&self) -> Option<usize> {
+        None
+    }
+
+    /// Maps a point with finite coordinates, or returns `None` if the point has no image.
+    fn transform(&self, coords: &[T]) -> Option<Vec<T>>;
+
+    /// Converts the Euclidean distance between two images into the distance between their
+    /// points.
+    fn distance_from_euclidean(&self, distance: T) -> T;
+
+    /// Converts a distance between points into the Euclidean distance between their images.
+    fn euclidean_radius(&self, radius: T) -> T;
+}
+
+/// An [`SRTree`] searching points by a distance other than a [`Metric`], through a
+/// [`PointTransform`].
+///
+/// Points are transformed when they are added and queries when they are run, so that the tree
+/// searches by Euclidean distance. Distances are converted back to the original distance before
+/// they are returned.
+///
+/// [`Metric`]: crate::Metric
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        deserialize = "T: Float + Send + Sync + serde::Deserialize<'de>, F: serde::Deserialize<'de>"
+    ))
+)]
+pub struct TransformedSRTree<T, F> {
+    tree: SRTree<T, Euclidean>,
+    transform: F,
+}
+
+impl<T, F> TransformedSRTree<T, F>
+where
+    T: Float + Send + Sync,
+    F: PointTransform<T>,
+{
+    /// Builds `TransformedSRTree` with the given points, transforming them.
+    ///
+    /// # Errors
+    /// * `Error::Empty` if the input array is empty.
+    /// * `Error::DimensionMismatch` if the input array contains points of different dimensions,
+    ///   or of a dimension the transform does not accept.
+    /// * `Error::NonFiniteCoordinate` if a point has a NaN or infinite coordinate.
+    /// * `Error::ZeroVector` if a point has no image, such as a point with no direction for
+    ///   [`Cosine`](crate::Cosine).
+    pub fn new(pts: &[Vec<T>], params: Params, transform: F) -> Result<Self, Error> {
+        let mut images = Vec::with_capacity(pts.len());
+        for (row, point) in pts.iter().enumerate() {
+            if let Some(expected) = transform.dimension() {
+                if point.len() != expected {
+                    return Err(Error::DimensionMismatch {
+                        row,
+                        expected,
+                        found: point.len(),
+                    });
+                }
+            }
+            if let Some(column) = point.iter().position(|coord| !coord.is_finite()) {
+                return Err(Error::NonFiniteCoordinate { row, column });
+            }
+            images.push(
+                transform
+                    .transform(point)
+                    .ok_or(Error::ZeroVector { row })?,
+            );
+        }
+        let tree = SRTree::from_vecs(images, params, Euclidean::default())?;
+        Ok(TransformedSRTree { tree, transform })
+    }
+
+    /// Wraps a tree of points that are already transformed, such as a tree saved from
+    /// [`TransformedSRTree::tree`] and loaded again.
+    pub fn from_tree(tree: SRTree<T, Euclidean>, transform: F) -> Self {
+        TransformedSRTree { tree, transform }
+    }
+
+    /// Returns the underlying tree of transformed points, searched by Euclidean distance.
+    #[must_use]
+    pub fn tree(&self) -> &SRTree<T, Euclidean> {
+        &self.tree
+    }
+
+    #[must_use]
+    pub fn transform(&self) -> &F {
+        &self.transform
+    }
+
+    #[must_use]
+    pub fn num_points(&self) -> usize {
+        self.tree.num_points()
+    }
+
+    /// Transforms a point, inserts it and returns its index.
+    ///
+    /// # Errors
+    /// * `Error::DimensionMismatch` if the dimension of `coords` differs from the dimension of
+    ///   the tree.
+    /// * `Error::NonFiniteCoordinate` if `coords` has a NaN or infinite coordinate.
+    /// * `Error::ZeroVector` if `coords` has no image.
+    ///
+    /// The index the point would have had is the row of an error.
+    pub fn insert(&mut self, coords: &[T]) -> Result<usize, Error> {
+        let image = self.image_of(self.tree.points.len(), coords)?;
+        self.tree.try_insert(image)
+    }
+
+    /// Moves the point with the given index to new coordinates, keeping its index. Returns
+    /// `false` if there is no such point.
+    ///
+    /// # Errors
+    /// * `Error::DimensionMismatch` if the dimension of `coords` differs from the dimension of
+    ///   the tree.
+    /// * `Error::NonFiniteCoordinate` if `coords` has a NaN or infinite coordinate.
+    /// * `Error::ZeroVector` if `coords` has no image.
+    ///
+    /// `point_index` is the row of an error.
+    pub fn update(&mut self, point_index: usize, coords: &[T]) -> Result<bool, Error> {
+        let image = self.image_of(point_index, coords)?;
+        self.tree.try_update(point_index, image)
+    }
+
+    /// Removes the point with the given index, returning `false` if there is no such point.
+    pub fn remove(&mut self, point_index: usize) -> bool {
+        self.tree.remove(point_index)
+    }
+
+    /// Finds the `k` nearest neighbors and returns their indices and distances, from the
+    /// closest one.
+    ///
+    /// Returns no neighbor if `point_coords` has a NaN or infinite coordinate, or no image.
+    ///
+    /// # Panics
+    /// * If the dimension of `point_coords` differs from the dimension of the tree; see
+    ///   [`TransformedSRTree::try_query`].
+    #[must_use]
+    pub fn query(&self, point_coords: &[T], k: usize) -> (Vec<usize>, Vec<T>) {
+        let (indices, distances) = match self.image(point_coords) {
+            Some(image) => self.tree.query(&image, k),
+            None => return (Vec::new(), Vec::new()),
+        };
+        let distances = distances
+            .into_iter()
+            .map(|distance| self.transform.distance_from_euclidean(distance))
+            .collect();
+        (indices, distances)
+    }
+
+    /// Returns the indices of the points within `radius`, in no particular order.
+    ///
+    /// Returns no point if `point_coords` has a NaN or infinite coordinate, or no image.
+    ///
+    /// # Panics
+    /// * If the dimension of `point_coords` differs from the dimension of the tree; see
+    ///   [`TransformedSRTree::try_query_radius`].
+    #[must_use]
+    pub fn query_radius(&self, point_coords: &[T], radius: T) -> Vec<usize> {
+        match self.image(point_coords) {
+            Some(image) => self
+                .tree
+                .query_radius(&image, self.transform.euclidean_radius(radius)),
+            None => Vec::new(),
+        }
+    }
+
+    /// Runs [`TransformedSRTree::query`] after checking the dimension of `point_coords`.
+    ///
+    /// # Errors
+    /// * `Error::QueryDimensionMismatch` if `point_coords` does not have the dimension of the
+    ///   tree.
+    pub fn try_query(&self, point_coords: &[T], k: usize) -> Result<(Vec<usize>, Vec<T>), Error> {
+        self.tree.check_query_dimension(point_coords)?;
+        Ok(self.query(point_coords, k))
+    }
+
+    /// Runs [`TransformedSRTree::query_radius`] after checking the dimension of `point_coords`.
+    ///
+    /// # Errors
+    /// * `Error::QueryDimensionMismatch` if `point_coords` does not have the dimension of the
+    ///   tree.
+    pub fn try_query_radius(&self, point_coords: &[T], radius: T) -> Result<Vec<usize>, Error> {
+        self.tree.check_query_dimension(point_coords)?;
+        Ok(self.query_radius(point_coords, radius))
+    }
+
+    /// Checks and transforms a point that is added to the tree as the point at `row`.
+    fn image_of(&self, row: usize, coords: &[T]) -> Result<Vec<T>, Error> {
+        self.tree.check_point(row, coords)?;
+        self.transform
+            .transform(coords)
+            .ok_or(Error::ZeroVector { row })
+    }
+
+    /// Transforms a query point of the dimension of the tree, or returns `None` if it has a
+    /// non-finite coordinate or no image.
+    fn image(&self, coords: &[T]) -> Option<Vec<T>> {
+        assert_eq!(
+            coords.len(),
+            self.tree.params.dimension,
+            "point dimension does not match the tree"
+        );
+        if coords.iter().any(|coord| !coord.is_finite()) {
+            return None;
+        }
+        self.transform.transform(coords)
+    }
+}
@@ -0,0 +1,81 @@
+use ordered_float::OrderedFloat;
+use rand::prelude::*;
+use srtree::{Angular, Cosine, Params, PointTransform, TransformedSRTree};
+
+pub fn cosine_distance(point1: &[f64], point2: &[f64]) -> f64 {
+    let dot: f64 = point1.iter().zip(point2).map(|(a, b)| a * b).sum();
+    let norm1 = point1.iter().map(|a| a * a).sum::<f64>().sqrt();
+    let norm2 = point2.iter().map(|b| b * b).sum::<f64>().sqrt();
+    1. - dot / (norm1 * norm2)
+}
+
+pub fn angular_distance(point1: &[f64], point2: &[f64]) -> f64 {
+    let norm1 = point1.iter().map(|a| a * a).sum::<f64>().sqrt();
+    let norm2 = point2.iter().map(|b| b * b).sum::<f64>().sqrt();
+    let (mut difference, mut sum) = (0., 0.);
+    for (a, b) in point1.iter().zip(point2) {
+        difference += (a / norm1 - b / norm2).powi(2);
+        sum += (a / norm1 + b / norm2).powi(2);
+    }
+    2. * difference.sqrt().atan2(sum.sqrt())
+}
+
+#[test]
+fn test_with_random_points() {
+    const DIMENSION: usize = 8;
+    let number_of_points = 1000;
+
+    let mut rng = rand::thread_rng();
+    let mut pts = Vec::new();
+    for _ in 0..number_of_points {
+        let mut point_coords = Vec::new();
+        for _ in 0..DIMENSION {
+            let x: f64 = rng.gen::<f64>() * 2. - 1.;
+            point_coords.push(x);
+        }
+        pts.push(point_coords);
+    }
+
+    check_transform(&pts, Cosine::default(), cosine_distance, 0.3);
+    check_transform(&pts, Angular::default(), angular_distance, 0.8);
+}
+
+fn check_transform<F, D>(pts: &[Vec<f64>], transform: F, distance: D, radius: f64)
+where
+    F: PointTransform<f64>,
+    D: Fn(&[f64], &[f64]) -> f64,
+{
+    let k = 10;
+
+    // Bulk-load half of the points and insert the rest one by one
+    let half = pts.len() / 2;
+    let mut tree = TransformedSRTree::new(&pts[..half], Params::new(4, 10).unwrap(), transform)
+        .expect("Failed to build TransformedSRTree");
+    for p in &pts[half..] {
+        tree.insert(p).unwrap();
+    }
+    assert_eq!(tree.num_points(), pts.len());
+
+    let mut points = pts.to_vec();
+    for p in pts.iter().step_by(10) {
+        let query: Vec<f64> = p.iter().map(|x| x * 3.).collect();
+        let (_, distances) = tree.query(&query, k);
+
+        let mut result = tree.query_radius(&query, radius);
+        result.sort();
+        let brute_force_result: Vec<usize> = pts
+            .iter()
+            .enumerate()
+            .filter(|(_, point)| distance(point, &query) <= radius)
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(result, brute_force_result);
+
+        // Brute-force
+        points.sort_by_key(|a| OrderedFloat(distance(a, &query)));
+        for i in 0..k {
+            let distance_brute_force = distance(&points[i], &query);
+            assert!((distances[i] - distance_brute_force).abs() < 1e-9);
+        }
+    }
+}
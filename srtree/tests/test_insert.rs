@@ -0,0 +1,76 @@
+use ordered_float::OrderedFloat;
+use rand::prelude::*;
+use srtree::{Params, SRTree};
+
+pub fn euclidean_squared(point1: &[f64], point2: &[f64]) -> f64 {
+    if point1.len() != point2.len() {
+        return f64::INFINITY;
+    }
+    let mut distance = 0.;
+    for i in 0..point1.len() {
+        distance += (point1[i] - point2[i]).powi(2);
+    }
+    distance
+}
+
+#[test]
+fn test_with_random_points() {
+    const DIMENSION: usize = 3;
+    let number_of_points = 1000;
+    let k = 10;
+    let radius = 0.1;
+
+    let mut rng = rand::thread_rng();
+    let mut pts = Vec::new();
+    for _ in 0..number_of_points {
+        let mut point_coords = Vec::new();
+        for _ in 0..DIMENSION {
+            let x: f64 = rng.gen::<f64>();
+            point_coords.push(x);
+        }
+        pts.push(point_coords);
+    }
+
+    // With and without forced reinsertion on overflow
+    for reinsertion_fraction in [0., 0.3] {
+        let params = Params::new(4, 10)
+            .unwrap()
+            .with_reinsertion_fraction(reinsertion_fraction)
+            .unwrap();
+        check_insertion(&pts, params, k, radius);
+    }
+}
+
+fn check_insertion(pts: &[Vec<f64>], params: Params, k: usize, radius: f64) {
+    // Bulk-load the first tenth of the points and insert the rest one by one
+    let mut tree =
+        SRTree::euclidean_with_params(&pts[..100], params).expect("Failed to build SRTree");
+    for p in pts.iter().skip(100) {
+        tree.insert(p.clone());
+    }
+    assert_eq!(tree.num_points(), pts.len());
+    assert_eq!(tree.validate(), Ok(()));
+
+    let mut points = pts.to_vec();
+    for p in pts.iter() {
+        let (indices, distances) = tree.query(p, k);
+        assert_eq!(indices.len(), k);
+
+        let mut result = tree.query_radius(p, radius);
+        result.sort();
+        let brute_force_result: Vec<usize> = pts
+            .iter()
+            .enumerate()
+            .filter(|(_, point)| euclidean_squared(point, p).sqrt() <= radius)
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(result, brute_force_result);
+
+        // Brute-force
+        points.sort_by_key(|a| OrderedFloat(euclidean_squared(a, p)));
+        for i in 0..k {
+            let distance_brute_force = euclidean_squared(&points[i], p).sqrt();
+            assert_eq!(distances[i], distance_brute_force);
+        }
+    }
+}
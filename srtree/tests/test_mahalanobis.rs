@@ -0,0 +1,104 @@
+use ordered_float::OrderedFloat;
+use rand::prelude::*;
+use srtree::{Mahalanobis, MahalanobisSRTree, Params};
+
+/// Returns `sqrt(vᵀ Σ⁻¹ v)` for `v = point1 - point2`, solving `Σ x = v` by Gaussian
+/// elimination.
+fn mahalanobis(covariance: &[Vec<f64>], point1: &[f64], point2: &[f64]) -> f64 {
+    let n = covariance.len();
+    let v: Vec<f64> = point1.iter().zip(point2).map(|(a, b)| a - b).collect();
+    let mut matrix: Vec<Vec<f64>> = covariance
+        .iter()
+        .zip(&v)
+        .map(|(row, value)| {
+            let mut row = row.clone();
+            row.push(*value);
+            row
+        })
+        .collect();
+    for i in 0..n {
+        let pivot = matrix[i].clone();
+        for row in &mut matrix[i + 1..] {
+            let factor = row[i] / pivot[i];
+            for (entry, value) in row.iter_mut().zip(&pivot).skip(i) {
+                *entry -= factor * value;
+            }
+        }
+    }
+    let mut x = vec![0.; n];
+    for i in (0..n).rev() {
+        let sum: f64 = (i + 1..n).map(|k| matrix[i][k] * x[k]).sum();
+        x[i] = (matrix[i][n] - sum) / matrix[i][i];
+    }
+    v.iter().zip(&x).map(|(a, b)| a * b).sum::<f64>().sqrt()
+}
+
+#[test]
+fn test_with_random_points() {
+    const DIMENSION: usize = 4;
+    let number_of_points = 1000;
+    let k = 10;
+    let radius = 0.5;
+
+    let mut rng = rand::thread_rng();
+    let mut pts = Vec::new();
+    for _ in 0..number_of_points {
+        let mut point_coords = Vec::new();
+        for _ in 0..DIMENSION {
+            let x: f64 = rng.gen::<f64>();
+            point_coords.push(x);
+        }
+        pts.push(point_coords);
+    }
+
+    // A random positive definite matrix A Aᵀ + I
+    let a: Vec<Vec<f64>> = (0..DIMENSION)
+        .map(|_| (0..DIMENSION).map(|_| rng.gen::<f64>() - 0.5).collect())
+        .collect();
+    let covariance: Vec<Vec<f64>> = (0..DIMENSION)
+        .map(|i| {
+            (0..DIMENSION)
+                .map(|j| {
+                    let dot: f64 = (0..DIMENSION).map(|k| a[i][k] * a[j][k]).sum();
+                    if i == j {
+                        dot + 0.1
+                    } else {
+                        dot
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let metric = Mahalanobis::from_covariance(&covariance).unwrap();
+
+    // Bulk-load half of the points and insert the rest one by one
+    let half = number_of_points / 2;
+    let mut tree = MahalanobisSRTree::new(&pts[..half], Params::new(4, 10).unwrap(), metric)
+        .expect("Failed to build MahalanobisSRTree");
+    for p in &pts[half..] {
+        tree.insert(p).unwrap();
+    }
+    assert_eq!(tree.num_points(), number_of_points);
+
+    let mut points = pts.clone();
+    for query in pts.iter().step_by(10) {
+        let (_, distances) = tree.query(query, k);
+        points.sort_by_key(|a| OrderedFloat(mahalanobis(&covariance, a, query)));
+        for i in 0..k {
+            let distance_brute_force = mahalanobis(&covariance, &points[i], query);
+            assert!((distances[i] - distance_brute_force).abs() < 1e-9);
+            let distance = tree.transform().distance(&points[i], query);
+            assert!((distance - distance_brute_force).abs() < 1e-9);
+        }
+
+        let mut result = tree.query_radius(query, radius);
+        result.sort();
+        let brute_force_result: Vec<usize> = pts
+            .iter()
+            .enumerate()
+            .filter(|(_, point)| mahalanobis(&covariance, point, query) <= radius)
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(result, brute_force_result);
+    }
+}
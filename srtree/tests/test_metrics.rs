@@ -0,0 +1,97 @@
+use ordered_float::OrderedFloat;
+use rand::prelude::*;
+use srtree::{Chebyshev, Manhattan, Metric, Minkowski, Params, SRTree, WeightedEuclidean};
+
+fn minkowski(point1: &[f64], point2: &[f64], p: f64) -> f64 {
+    if p.is_infinite() {
+        return point1
+            .iter()
+            .zip(point2)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0., f64::max);
+    }
+    point1
+        .iter()
+        .zip(point2)
+        .map(|(a, b)| (a - b).abs().powf(p))
+        .sum::<f64>()
+        .powf(p.recip())
+}
+
+#[test]
+fn test_with_random_points() {
+    const DIMENSION: usize = 4;
+    let number_of_points = 1000;
+
+    let mut rng = rand::thread_rng();
+    let mut pts = Vec::new();
+    for _ in 0..number_of_points {
+        let mut point_coords = Vec::new();
+        for _ in 0..DIMENSION {
+            let x: f64 = rng.gen::<f64>();
+            point_coords.push(x);
+        }
+        pts.push(point_coords);
+    }
+
+    check_metric(&pts, Manhattan::default(), |a, b| minkowski(a, b, 1.));
+    check_metric(&pts, Chebyshev::default(), |a, b| {
+        minkowski(a, b, f64::INFINITY)
+    });
+    check_metric(&pts, Minkowski::new(3.).unwrap(), |a, b| {
+        minkowski(a, b, 3.)
+    });
+    check_metric(&pts, Minkowski::new(1.5).unwrap(), |a, b| {
+        minkowski(a, b, 1.5)
+    });
+
+    let weights = vec![0.5, 2., 0., 1.];
+    let weighted_euclidean = |a: &[f64], b: &[f64]| {
+        a.iter()
+            .zip(b)
+            .zip(&weights)
+            .map(|((a, b), w)| w * (a - b).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    };
+    let metric = WeightedEuclidean::new(weights.clone()).unwrap();
+    check_metric(&pts, metric, weighted_euclidean);
+}
+
+fn check_metric<M, F>(pts: &[Vec<f64>], metric: M, distance: F)
+where
+    M: Metric<f64>,
+    F: Fn(&[f64], &[f64]) -> f64,
+{
+    let k = 10;
+    let radius = 0.2;
+
+    // Bulk-load half of the points and insert the rest one by one
+    let half = pts.len() / 2;
+    let mut tree = SRTree::new(&pts[..half], Params::new(4, 10).unwrap(), metric)
+        .expect("Failed to build SRTree");
+    for p in &pts[half..] {
+        tree.insert(p.clone());
+    }
+    assert_eq!(tree.validate(), Ok(()));
+
+    let mut points = pts.to_vec();
+    for query in pts.iter().step_by(10) {
+        let (_, distances) = tree.query(query, k);
+        points.sort_by_key(|a| OrderedFloat(distance(a, query)));
+        for i in 0..k {
+            let distance_brute_force = distance(&points[i], query);
+            assert!((distances[i] - distance_brute_force).abs() < 1e-12);
+        }
+
+        let mut result = tree.query_radius(query, radius);
+        result.sort();
+        let brute_force_result: Vec<usize> = pts
+            .iter()
+            .enumerate()
+            .filter(|(_, point)| distance(point, query) <= radius)
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(result, brute_force_result);
+    }
+}
@@ -0,0 +1,51 @@
+use ordered_float::OrderedFloat;
+use rand::prelude::*;
+use srtree::SRTree;
+
+pub fn euclidean_squared(point1: &[f64], point2: &[f64]) -> f64 {
+    if point1.len() != point2.len() {
+        return f64::INFINITY;
+    }
+    let mut distance = 0.;
+    for i in 0..point1.len() {
+        distance += (point1[i] - point2[i]).powi(2);
+    }
+    distance
+}
+
+#[test]
+fn test_with_random_points() {
+    const DIMENSION: usize = 4;
+    let number_of_points = 1000;
+
+    let mut rng = rand::thread_rng();
+    let mut pts = Vec::new();
+    for _ in 0..number_of_points {
+        let mut point_coords = Vec::new();
+        for _ in 0..DIMENSION {
+            let x: f64 = rng.gen::<f64>();
+            point_coords.push(x);
+        }
+        pts.push(point_coords);
+    }
+
+    let tree = SRTree::euclidean(&pts).expect("Failed to build SRTree");
+    let mut points = pts.clone();
+    for p in pts.iter().take(100) {
+        // Brute-force
+        points.sort_by_key(|a| OrderedFloat(euclidean_squared(a, p)));
+
+        let mut count = 0;
+        for (i, (index, distance)) in tree.nearest_iter(p).enumerate() {
+            assert_eq!(distance, euclidean_squared(&points[i], p).sqrt());
+            assert_eq!(distance, euclidean_squared(&pts[index], p).sqrt());
+            count += 1;
+        }
+        assert_eq!(count, number_of_points);
+
+        // Agrees with k-NN query
+        let (_, distances) = tree.query(p, 15);
+        let iter_distances: Vec<f64> = tree.nearest_iter(p).take(15).map(|(_, d)| d).collect();
+        assert_eq!(distances, iter_distances);
+    }
+}
@@ -46,3 +46,37 @@ fn test_with_random_points() {
         }
     }
 }
+
+#[test]
+fn test_filtered_with_random_points() {
+    const DIMENSION: usize = 2;
+    let number_of_points = 1000;
+    let k = 10;
+
+    let mut rng = rand::thread_rng();
+    let mut pts = Vec::new();
+    for _ in 0..number_of_points {
+        let mut point_coords = Vec::new();
+        for _ in 0..DIMENSION {
+            let x: f64 = rng.gen::<f64>();
+            point_coords.push(x);
+        }
+        pts.push(point_coords);
+    }
+
+    let bulk_tree = SRTree::euclidean(&pts).expect("Failed to build SRTree");
+    let mut points: Vec<&Vec<f64>> = pts.iter().step_by(7).collect();
+    for p in pts.iter() {
+        let (indices, distances) = bulk_tree.query_filtered(p, k, |i| i % 7 == 0);
+        assert_eq!(indices.len(), k);
+        assert!(indices.iter().all(|i| i % 7 == 0));
+
+        // Brute-force
+        points.sort_by_key(|a| OrderedFloat(euclidean_squared(a, p)));
+
+        for i in 0..k {
+            let distance_brute_force = euclidean_squared(points[i], p).sqrt();
+            assert_eq!(distances[i], distance_brute_force);
+        }
+    }
+}
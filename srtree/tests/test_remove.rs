@@ -0,0 +1,82 @@
+use ordered_float::OrderedFloat;
+use rand::prelude::*;
+use srtree::{Params, SRTree};
+
+pub fn euclidean_squared(point1: &[f64], point2: &[f64]) -> f64 {
+    if point1.len() != point2.len() {
+        return f64::INFINITY;
+    }
+    let mut distance = 0.;
+    for i in 0..point1.len() {
+        distance += (point1[i] - point2[i]).powi(2);
+    }
+    distance
+}
+
+#[test]
+fn test_with_random_points() {
+    const DIMENSION: usize = 3;
+    let number_of_points = 1000;
+    let k = 10;
+    let radius = 0.1;
+
+    let mut rng = rand::thread_rng();
+    let mut pts = Vec::new();
+    for _ in 0..number_of_points {
+        let mut point_coords = Vec::new();
+        for _ in 0..DIMENSION {
+            let x: f64 = rng.gen::<f64>();
+            point_coords.push(x);
+        }
+        pts.push(point_coords);
+    }
+
+    let mut tree = SRTree::euclidean_with_params(&pts, Params::new(4, 10).unwrap())
+        .expect("Failed to build SRTree");
+
+    // Remove a random half of the points
+    let mut removed = vec![false; number_of_points];
+    let mut order: Vec<usize> = (0..number_of_points).collect();
+    order.shuffle(&mut rng);
+    for index in order.into_iter().take(number_of_points / 2) {
+        assert!(tree.remove(index));
+        removed[index] = true;
+    }
+    assert_eq!(tree.num_points(), number_of_points / 2);
+    assert_eq!(tree.validate(), Ok(()));
+
+    let remaining: Vec<(usize, &Vec<f64>)> = pts
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !removed[*i])
+        .collect();
+    for p in pts.iter() {
+        let (indices, distances) = tree.query(p, k);
+        assert_eq!(indices.len(), k);
+        assert!(indices.iter().all(|index| !removed[*index]));
+
+        let mut result = tree.query_radius(p, radius);
+        result.sort();
+        let brute_force_result: Vec<usize> = remaining
+            .iter()
+            .filter(|(_, point)| euclidean_squared(point, p).sqrt() <= radius)
+            .map(|(index, _)| *index)
+            .collect();
+        assert_eq!(result, brute_force_result);
+
+        // Brute-force
+        let mut points: Vec<&Vec<f64>> = remaining.iter().map(|(_, point)| *point).collect();
+        points.sort_by_key(|a| OrderedFloat(euclidean_squared(a, p)));
+        for i in 0..k {
+            let distance_brute_force = euclidean_squared(points[i], p).sqrt();
+            assert_eq!(distances[i], distance_brute_force);
+        }
+    }
+
+    // Remove everything that is left
+    for (index, _) in remaining {
+        assert!(tree.remove(index));
+    }
+    assert_eq!(tree.num_points(), 0);
+    assert_eq!(tree.num_nodes(), 0);
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "serde")]
+
+use rand::prelude::*;
+use srtree::{Euclidean, Mahalanobis, Minkowski, Params, SRTree, WeightedEuclidean};
+
+#[test]
+fn test_json_round_trip() {
+    const DIMENSION: usize = 3;
+    let number_of_points = 1000;
+    let k = 10;
+
+    let mut rng = rand::thread_rng();
+    let mut pts = Vec::new();
+    for _ in 0..number_of_points {
+        let mut point_coords = Vec::new();
+        for _ in 0..DIMENSION {
+            let x: f64 = rng.gen::<f64>();
+            point_coords.push(x);
+        }
+        pts.push(point_coords);
+    }
+
+    let mut tree = SRTree::euclidean_with_params(&pts, Params::new(4, 10).unwrap())
+        .expect("Failed to build SRTree");
+    tree.remove(0);
+    tree.insert(vec![0.5; DIMENSION]);
+
+    let json = serde_json::to_string(&tree).expect("Failed to serialize SRTree");
+    let loaded: SRTree<f64, Euclidean> =
+        serde_json::from_str(&json).expect("Failed to deserialize SRTree");
+    assert_eq!(loaded.num_points(), tree.num_points());
+    assert_eq!(loaded.num_nodes(), tree.num_nodes());
+    assert_eq!(loaded.height(), tree.height());
+
+    for p in pts.iter() {
+        assert_eq!(loaded.query(p, k), tree.query(p, k));
+        assert_eq!(loaded.query_radius(p, 0.1), tree.query_radius(p, 0.1));
+    }
+}
+
+#[test]
+fn test_invalid_metric() {
+    let metric: Minkowski = serde_json::from_str(r#"{"p":3.0}"#).unwrap();
+    assert_eq!(metric.p(), 3.);
+    assert!(serde_json::from_str::<Minkowski>(r#"{"p":0.5}"#).is_err());
+
+    let weights = r#"{"weights":[1.0,-1.0]}"#;
+    assert!(serde_json::from_str::<WeightedEuclidean<f64>>(weights).is_err());
+    let whitening = r#"{"whitening":[1.0,0.0,0.0],"dimension":2}"#;
+    assert!(serde_json::from_str::<Mahalanobis<f64>>(whitening).is_err());
+    let metric = Mahalanobis::from_covariance(&[vec![4., 0.], vec![0., 1.]]).unwrap();
+    let json = serde_json::to_string(&metric).unwrap();
+    let loaded: Mahalanobis<f64> = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        loaded.distance(&[2., 1.], &[0., 0.]),
+        metric.distance(&[2., 1.], &[0., 0.])
+    );
+}
+
+#[test]
+fn test_metric_dimension_mismatch() {
+    let pts = vec![vec![0., 0.], vec![1., 2.], vec![3., 1.]];
+    let weighted = WeightedEuclidean::new(vec![1., 4.]).unwrap();
+    let tree = SRTree::new(&pts, Params::default_params(), weighted).unwrap();
+    let json = serde_json::to_string(&tree).unwrap();
+    let loaded: SRTree<f64, WeightedEuclidean<f64>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.query(&[1., 1.], 1), tree.query(&[1., 1.], 1));
+
+    let json = json.replace("[1.0,4.0]", "[1.0,4.0,1.0]");
+    let result = serde_json::from_str::<SRTree<f64, WeightedEuclidean<f64>>>(&json);
+    assert!(result.is_err());
+}
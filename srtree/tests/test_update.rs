@@ -0,0 +1,63 @@
+use ordered_float::OrderedFloat;
+use rand::prelude::*;
+use srtree::{Params, SRTree};
+
+pub fn euclidean_squared(point1: &[f64], point2: &[f64]) -> f64 {
+    if point1.len() != point2.len() {
+        return f64::INFINITY;
+    }
+    let mut distance = 0.;
+    for i in 0..point1.len() {
+        distance += (point1[i] - point2[i]).powi(2);
+    }
+    distance
+}
+
+#[test]
+fn test_with_random_points() {
+    const DIMENSION: usize = 3;
+    let number_of_points = 1000;
+    let k = 10;
+
+    let mut rng = rand::thread_rng();
+    let mut pts = Vec::new();
+    for _ in 0..number_of_points {
+        let mut point_coords = Vec::new();
+        for _ in 0..DIMENSION {
+            let x: f64 = rng.gen::<f64>();
+            point_coords.push(x);
+        }
+        pts.push(point_coords);
+    }
+
+    let mut tree = SRTree::euclidean_with_params(&pts, Params::new(4, 10).unwrap())
+        .expect("Failed to build SRTree");
+
+    // Move every point: small drifts mostly stay in the leaf, large jumps do not
+    for (index, point) in pts.iter_mut().enumerate() {
+        let scale = if index % 2 == 0 { 0.01 } else { 1. };
+        for x in point.iter_mut() {
+            *x += (rng.gen::<f64>() - 0.5) * scale;
+        }
+        assert!(tree.update(index, point.clone()));
+    }
+    assert_eq!(tree.num_points(), number_of_points);
+    assert_eq!(tree.validate(), Ok(()));
+
+    let mut points: Vec<(usize, Vec<f64>)> = pts.clone().into_iter().enumerate().collect();
+    for p in pts.iter() {
+        let (indices, distances) = tree.query(p, k);
+        assert_eq!(indices.len(), k);
+
+        // Brute-force
+        points.sort_by_key(|(_, a)| OrderedFloat(euclidean_squared(a, p)));
+        for i in 0..k {
+            let distance_brute_force = euclidean_squared(&points[i].1, p).sqrt();
+            assert_eq!(distances[i], distance_brute_force);
+            assert_eq!(
+                euclidean_squared(&pts[indices[i]], p).sqrt(),
+                distance_brute_force
+            );
+        }
+    }
+}